@@ -1,11 +1,14 @@
-use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
-use imstr::{data::Data, string::*};
+use criterion::{
+    black_box, criterion_group, criterion_main, measurement::WallTime, BatchSize, BenchmarkGroup,
+    BenchmarkId, Criterion, Throughput,
+};
+use imstr::{data::Data, indexed::Indexed, string::*};
 use std::time::Duration;
 
 type Boxed = Box<String>;
 type Cloned = imstr::data::Cloned<String>;
 
-static INPUT: &'static str = include_str!("../src/string.rs");
+static INPUT: &str = include_str!("../src/string.rs");
 
 fn clone_repeat<S: Data<String>>(input: &ImString<S>, count: u64) {
     for _ in 0..count {
@@ -18,8 +21,8 @@ pub fn clone(c: &mut Criterion) {
     g.warm_up_time(Duration::from_millis(200));
     g.measurement_time(Duration::from_millis(200));
 
-    for size in [1, 1_000, 1_000_0] {
-        g.throughput(Throughput::Elements(size as u64));
+    for size in [1, 1_000, 10_000] {
+        g.throughput(Throughput::Elements(size));
 
         let string: ImString<Threadsafe> = ImString::from(INPUT);
         g.bench_with_input(
@@ -88,6 +91,73 @@ pub fn slice(c: &mut Criterion) {
     g.finish();
 }
 
+fn as_str_repeat<S: Data<String>>(input: &ImString<S>, count: u64) {
+    for _ in 0..count {
+        black_box(black_box(input).as_str());
+    }
+}
+
+pub fn as_str(c: &mut Criterion) {
+    let mut g = c.benchmark_group("as_str");
+    g.warm_up_time(Duration::from_millis(200));
+    g.measurement_time(Duration::from_millis(200));
+
+    for size in [1, 1_000, 10_000] {
+        g.throughput(Throughput::Elements(size));
+
+        let string: ImString<Threadsafe> = ImString::from(INPUT);
+        g.bench_with_input(
+            BenchmarkId::new("imstr-threadsafe", size),
+            &size,
+            |b, &s| b.iter(|| as_str_repeat(&string, s)),
+        );
+
+        let string: ImString<Local> = ImString::from(INPUT);
+        g.bench_with_input(BenchmarkId::new("imstr-local", size), &size, |b, &s| {
+            b.iter(|| as_str_repeat(&string, s))
+        });
+
+        let string: ImString<Boxed> = ImString::from(INPUT);
+        g.bench_with_input(BenchmarkId::new("imstr-boxed", size), &size, |b, &s| {
+            b.iter(|| as_str_repeat(&string, s))
+        });
+
+        let string: ImString<Cloned> = ImString::from(INPUT);
+        g.bench_with_input(BenchmarkId::new("imstr-cloned", size), &size, |b, &s| {
+            b.iter(|| as_str_repeat(&string, s))
+        });
+
+        let string: std::sync::Arc<str> = INPUT.into();
+        g.bench_with_input(BenchmarkId::new("arc-str", size), &size, |b, &s| {
+            b.iter(|| {
+                for _ in 0..s {
+                    black_box(black_box(&string).as_ref());
+                }
+            })
+        });
+    }
+
+    g.finish();
+}
+
+pub fn char_count(c: &mut Criterion) {
+    let mut g = c.benchmark_group("char_count");
+    g.warm_up_time(Duration::from_millis(200));
+    g.measurement_time(Duration::from_millis(200));
+
+    let string: ImString<Threadsafe> = ImString::from(INPUT);
+    g.bench_function("recompute", |b| {
+        b.iter(|| black_box(black_box(&string).chars().count()))
+    });
+
+    let string: Indexed<Threadsafe> = Indexed::new(ImString::from(INPUT));
+    g.bench_function("indexed", |b| {
+        b.iter(|| black_box(black_box(&string).char_count()))
+    });
+
+    g.finish();
+}
+
 fn push_repeat<S: Data<String>>(input: &ImString<S>, count: u64) {
     let mut string = input.clone();
     for _ in 0..count {
@@ -102,7 +172,7 @@ pub fn push(c: &mut Criterion) {
     g.measurement_time(Duration::from_millis(200));
 
     for size in [100, 10_000, 1_000_000] {
-        g.throughput(Throughput::Elements(size as u64));
+        g.throughput(Throughput::Elements(size));
 
         let string: ImString<Threadsafe> = ImString::from(INPUT);
         g.bench_with_input(
@@ -130,5 +200,114 @@ pub fn push(c: &mut Criterion) {
     g.finish();
 }
 
-criterion_group!(benches, clone, slice, push);
+fn find_backing<S: Data<String>>(g: &mut BenchmarkGroup<WallTime>, name: &str, input: &str) {
+    let string: ImString<S> = ImString::from(input);
+    g.bench_function(BenchmarkId::new("find-char", name), |b| {
+        b.iter(|| black_box(black_box(&string).as_str().find('\u{7}')))
+    });
+
+    #[cfg(feature = "memchr")]
+    g.bench_function(BenchmarkId::new("find-byte", name), |b| {
+        b.iter(|| black_box(black_box(&string).find_byte(7)))
+    });
+
+    g.bench_function(BenchmarkId::new("rfind-char", name), |b| {
+        b.iter(|| black_box(black_box(&string).as_str().rfind('\0')))
+    });
+
+    #[cfg(feature = "memchr")]
+    g.bench_function(BenchmarkId::new("rfind-byte", name), |b| {
+        b.iter(|| black_box(black_box(&string).rfind_byte(0)))
+    });
+}
+
+pub fn find(c: &mut Criterion) {
+    let mut g = c.benchmark_group("find");
+    g.warm_up_time(Duration::from_millis(200));
+    g.measurement_time(Duration::from_millis(200));
+
+    // repeat the input so that the needle is far away from the start
+    let input = INPUT.repeat(16) + "\u{7}";
+    g.throughput(Throughput::Bytes(input.len() as u64));
+
+    find_backing::<Threadsafe>(&mut g, "imstr-threadsafe", &input);
+    find_backing::<Local>(&mut g, "imstr-local", &input);
+    find_backing::<Boxed>(&mut g, "imstr-boxed", &input);
+    find_backing::<Cloned>(&mut g, "imstr-cloned", &input);
+
+    g.finish();
+}
+
+fn into_std_string_backing<S: Data<String>>(g: &mut BenchmarkGroup<WallTime>, name: &str) {
+    // unique backing, the string can be reused by draining the prefix
+    g.bench_function(BenchmarkId::new("unique", name), |b| {
+        b.iter_batched(
+            || ImString::<S>::from(INPUT).slice(INPUT.len() / 2..),
+            |string| black_box(string.into_std_string()),
+            BatchSize::SmallInput,
+        )
+    });
+
+    // shared backing, the contents have to be copied
+    let string: ImString<S> = ImString::from(INPUT);
+    g.bench_function(BenchmarkId::new("shared", name), |b| {
+        b.iter_batched(
+            || string.slice(INPUT.len() / 2..),
+            |string| black_box(string.into_std_string()),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+pub fn into_std_string(c: &mut Criterion) {
+    let mut g = c.benchmark_group("into_std_string");
+    g.warm_up_time(Duration::from_millis(200));
+    g.measurement_time(Duration::from_millis(200));
+
+    into_std_string_backing::<Threadsafe>(&mut g, "imstr-threadsafe");
+    into_std_string_backing::<Local>(&mut g, "imstr-local");
+    into_std_string_backing::<Boxed>(&mut g, "imstr-boxed");
+    into_std_string_backing::<Cloned>(&mut g, "imstr-cloned");
+
+    g.finish();
+}
+
+pub fn eq(c: &mut Criterion) {
+    let mut g = c.benchmark_group("eq");
+    g.warm_up_time(Duration::from_millis(200));
+    g.measurement_time(Duration::from_millis(200));
+    g.throughput(Throughput::Bytes(INPUT.len() as u64));
+
+    // clones share the backing string, so they are equal without comparing bytes
+    let string: ImString<Threadsafe> = ImString::from(INPUT);
+    let clone = string.clone();
+    g.bench_function("shared", |b| {
+        b.iter(|| black_box(black_box(&string) == black_box(&clone)))
+    });
+
+    // separately allocated strings have to be compared byte by byte
+    let other: ImString<Threadsafe> = ImString::from(INPUT);
+    g.bench_function("separate", |b| {
+        b.iter(|| black_box(black_box(&string) == black_box(&other)))
+    });
+
+    // the same comparison without the shared fast path
+    g.bench_function("str", |b| {
+        b.iter(|| black_box(black_box(string.as_str()) == black_box(clone.as_str())))
+    });
+
+    g.finish();
+}
+
+criterion_group!(
+    benches,
+    clone,
+    slice,
+    as_str,
+    char_count,
+    push,
+    find,
+    into_std_string,
+    eq
+);
 criterion_main!(benches);
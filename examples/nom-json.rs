@@ -162,7 +162,7 @@ fn json_value<E: ParseError<ImString> + ContextError<ImString>>(
         alt((
             map(hash, JsonValue::Object),
             map(array, JsonValue::Array),
-            map(string, |s| JsonValue::Str(s)),
+            map(string, JsonValue::Str),
             map(double, JsonValue::Num),
             map(boolean, JsonValue::Boolean),
             map(null, |_| JsonValue::Null),
@@ -0,0 +1,61 @@
+//! Conversion of [`ImString`] into [`Bytes`].
+//!
+//! This module is only available when the `bytes` feature is enabled. This crate is modelled on
+//! the [`Bytes`] type of the `bytes` crate, and this conversion lets the contents of an
+//! [`ImString`] be passed to APIs which take [`Bytes`], without copying them.
+use crate::data::Data;
+use crate::string::ImString;
+use ::bytes::Bytes;
+use alloc::string::String;
+
+impl<S: Data<String> + Send + 'static> ImString<S> {
+    /// Returns a [`Bytes`] holding the contents of this string, without copying them.
+    ///
+    /// The returned [`Bytes`] keeps a clone of this string alive and points into its backing
+    /// string, so no bytes are copied. Slicing the [`Bytes`] also does not copy. Because [`Bytes`]
+    /// can be sent between threads, this is only available for backing types which are [`Send`],
+    /// such as [`Threadsafe`](crate::string::Threadsafe).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("hello, world");
+    /// let bytes = string.slice(7..).to_bytes_shared();
+    /// assert_eq!(bytes, "world");
+    /// ```
+    pub fn to_bytes_shared(&self) -> Bytes {
+        Bytes::from_owner(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::string::{ImString, Threadsafe};
+
+    #[test]
+    fn test_to_bytes_shared() {
+        let inputs = ["", "a", "hello, world", "ütf-8 ✓"];
+        for input in inputs {
+            let string: ImString<Threadsafe> = ImString::from(input);
+            let bytes = string.to_bytes_shared();
+            assert_eq!(bytes, input.as_bytes());
+            assert_eq!(bytes.len(), string.len());
+        }
+    }
+
+    #[test]
+    fn test_to_bytes_shared_no_copy() {
+        let string: ImString<Threadsafe> = ImString::from("hello, world");
+        let world = string.slice(7..);
+        let bytes = world.to_bytes_shared();
+        assert_eq!(bytes, "world");
+        assert_eq!(bytes.as_ptr(), world.as_ptr());
+        assert_eq!(bytes.slice(1..3).as_ptr(), world.slice(1..3).as_ptr());
+
+        // the bytes keep the backing string alive
+        drop(world);
+        drop(string);
+        assert_eq!(bytes, "world");
+    }
+}
@@ -17,6 +17,30 @@ pub use alloc::sync::Arc;
 /// The `Data` trait is generic over the type of the inner value of the shared data, `T`.
 /// Implementers of this trait must provide methods to immutable access, and may provide methods to
 /// mutably access the data.
+///
+/// # Unsized storage
+///
+/// [`ImString`](crate::string::ImString) requires `Data<String>`, so there is no backing that
+/// stores an `Arc<str>` directly. Such a backing cannot hand out a `&String` from
+/// [`get()`](Data::get), and changing `get()` to return `&str` is not an option either, because
+/// this trait is generic over `T` and the in-place mutation of `ImString` relies on getting a
+/// `&mut String` with spare capacity. The savings would also be small: the capacity word is
+/// stored once per backing allocation rather than once per `ImString`, and a fat `Arc<str>`
+/// pointer would make every `ImString` one word larger. The `as_str` benchmark measures the cost
+/// of the extra indirection for the existing backings.
+///
+/// # Interior mutability
+///
+/// There is also no backing built on `Arc<Mutex<String>>`. A lock guard cannot be turned into
+/// the plain `&String` that [`get()`](Data::get) returns, and
+/// [`as_str()`](crate::string::ImString::as_str) and `Deref<Target = str>` hand out references
+/// which borrow from the `ImString` itself, not from a guard. Supporting such a backing would
+/// mean changing `get()` to return an associated guard type (for example
+/// `type Ref<'a>: Deref<Target = T>`), and removing `Deref`, `AsRef<str>` and every method that
+/// returns a borrowed `&str` from `ImString`. Another thread could also change the backing
+/// string underneath the offsets of an existing slice, so every access would have to check the
+/// offsets again. Coordinated mutation is better done by keeping a `Mutex<ImString>`, which
+/// keeps cloning and slicing cheap and the existing API intact.
 pub trait Data<T>: Clone {
     /// Create some new data.
     ///
@@ -107,6 +131,29 @@ pub trait Data<T>: Clone {
 
     /// Determine if `other` points to the same data as this.
     fn ptr_eq(&self, other: &Self) -> bool;
+
+    /// Returns the inner value, if this is the only reference to it.
+    ///
+    /// Otherwise, this is returned unchanged as the error. For [`Arc`] and [`Rc`], this succeeds
+    /// if and only if the reference count is 1, like [`Arc::try_unwrap()`]. Storage types which
+    /// do not share their data always succeed. The default implementation always fails, which is
+    /// correct, but means that callers have to fall back to [`get_mut()`](Data::get_mut) or copy
+    /// the data.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use imstr::data::{Data, Arc};
+    ///
+    /// let data = Arc::new(15);
+    /// let clone = data.clone();
+    /// let data = Data::try_unwrap(data).unwrap_err();
+    /// drop(clone);
+    /// assert_eq!(Data::try_unwrap(data), Ok(15));
+    /// ```
+    fn try_unwrap(self) -> Result<T, Self> {
+        Err(self)
+    }
 }
 
 impl<T> Data<T> for Arc<T> {
@@ -115,7 +162,7 @@ impl<T> Data<T> for Arc<T> {
     }
 
     fn get(&self) -> &T {
-        &self
+        self
     }
 
     fn get_mut(&mut self) -> Option<&mut T> {
@@ -123,7 +170,11 @@ impl<T> Data<T> for Arc<T> {
     }
 
     fn ptr_eq(&self, other: &Self) -> bool {
-        Arc::ptr_eq(&self, &other)
+        Arc::ptr_eq(self, other)
+    }
+
+    fn try_unwrap(self) -> Result<T, Self> {
+        Arc::try_unwrap(self)
     }
 }
 
@@ -133,7 +184,7 @@ impl<T> Data<T> for Rc<T> {
     }
 
     fn get(&self) -> &T {
-        &self
+        self
     }
 
     fn get_mut(&mut self) -> Option<&mut T> {
@@ -141,7 +192,11 @@ impl<T> Data<T> for Rc<T> {
     }
 
     fn ptr_eq(&self, other: &Self) -> bool {
-        Rc::ptr_eq(&self, &other)
+        Rc::ptr_eq(self, other)
+    }
+
+    fn try_unwrap(self) -> Result<T, Self> {
+        Rc::try_unwrap(self)
     }
 }
 
@@ -151,7 +206,7 @@ impl<T: Clone> Data<T> for Box<T> {
     }
 
     fn get(&self) -> &T {
-        &self
+        self
     }
 
     fn get_mut(&mut self) -> Option<&mut T> {
@@ -161,6 +216,33 @@ impl<T: Clone> Data<T> for Box<T> {
     fn ptr_eq(&self, other: &Self) -> bool {
         core::ptr::eq(&self, &other)
     }
+
+    fn try_unwrap(self) -> Result<T, Self> {
+        Ok(*self)
+    }
+}
+
+#[cfg(feature = "triomphe")]
+impl<T> Data<T> for triomphe::Arc<T> {
+    fn new(value: T) -> Self {
+        triomphe::Arc::new(value)
+    }
+
+    fn get(&self) -> &T {
+        self
+    }
+
+    fn get_mut(&mut self) -> Option<&mut T> {
+        triomphe::Arc::get_mut(self)
+    }
+
+    fn ptr_eq(&self, other: &Self) -> bool {
+        triomphe::Arc::ptr_eq(self, other)
+    }
+
+    fn try_unwrap(self) -> Result<T, Self> {
+        triomphe::Arc::try_unwrap(self)
+    }
 }
 
 /// Container for data which is not actually shared, but is cloned.
@@ -183,6 +265,10 @@ impl<T: Clone> Data<T> for Cloned<T> {
     fn ptr_eq(&self, other: &Self) -> bool {
         core::ptr::eq(&self.0, &other.0)
     }
+
+    fn try_unwrap(self) -> Result<T, Self> {
+        Ok(self.0)
+    }
 }
 
 #[cfg(test)]
@@ -199,6 +285,17 @@ mod tests {
         assert_eq!(number.get(), &20);
         let clone = number.clone();
         assert_eq!(clone.get(), number.get());
+        drop(clone);
+        assert_eq!(number.try_unwrap().ok(), Some(20));
+    }
+
+    fn test_try_unwrap_shared<T: Data<i32>>() {
+        let number = T::new(16);
+        let clone = number.clone();
+        let number = number.try_unwrap().unwrap_err();
+        assert_eq!(number.get(), &16);
+        drop(clone);
+        assert_eq!(number.try_unwrap().ok(), Some(16));
     }
 
     #[cfg(test)]
@@ -219,10 +316,19 @@ mod tests {
         test_i32::<Arc<i32>>();
         test_i32::<Rc<i32>>();
         test_i32::<Box<i32>>();
+        #[cfg(feature = "triomphe")]
+        test_i32::<triomphe::Arc<i32>>();
 
         test_string::<Cloned<String>>();
         test_string::<Arc<String>>();
         test_string::<Rc<String>>();
         test_string::<Box<String>>();
+        #[cfg(feature = "triomphe")]
+        test_string::<triomphe::Arc<String>>();
+
+        test_try_unwrap_shared::<Arc<i32>>();
+        test_try_unwrap_shared::<Rc<i32>>();
+        #[cfg(feature = "triomphe")]
+        test_try_unwrap_shared::<triomphe::Arc<i32>>();
     }
 }
@@ -1,6 +1,10 @@
 //! Error types
+pub use alloc::collections::TryReserveError;
 use alloc::fmt::{Display, Formatter, Result};
 pub use alloc::string::{FromUtf16Error, FromUtf8Error};
+pub use core::char::DecodeUtf16Error;
+use core::ops::Range;
+pub use core::str::Utf8Error;
 
 /// A possible error when slicing a [`ImString`](crate::ImString).
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,7 +33,106 @@ impl Display for SliceError {
     }
 }
 
+/// A [`SliceError`] together with the range and the length of the string it occurred for.
+///
+/// This is returned by [`try_slice_detailed()`](crate::string::ImString::try_slice_detailed).
+/// Unlike [`SliceError`], its [`Display`] output includes the offending offsets, which makes it
+/// more useful in error messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SliceErrorDetailed {
+    kind: SliceError,
+    range: Range<usize>,
+    length: usize,
+}
+
+impl SliceErrorDetailed {
+    pub(crate) fn new(kind: SliceError, range: Range<usize>, length: usize) -> Self {
+        SliceErrorDetailed {
+            kind,
+            range,
+            length,
+        }
+    }
+
+    /// Returns the reason the range is invalid.
+    pub fn kind(&self) -> SliceError {
+        self.kind
+    }
+
+    /// Returns the byte range which was used to slice the string.
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the length in bytes of the string which was sliced.
+    pub fn length(&self) -> usize {
+        self.length
+    }
+}
+
+impl Display for SliceErrorDetailed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "{}: range {}..{} for string of length {}",
+            self.kind, self.range.start, self.range.end, self.length
+        )
+    }
+}
+
+impl From<SliceErrorDetailed> for SliceError {
+    fn from(error: SliceErrorDetailed) -> Self {
+        error.kind
+    }
+}
+
+/// A possible error when decoding escape sequences with
+/// [`decode_escapes()`](crate::string::ImString::decode_escapes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscapeError {
+    /// String ends inside of an escape sequence.
+    UnexpectedEnd,
+    /// Backslash followed by a character which does not start an escape sequence.
+    UnknownEscape,
+    /// Malformed `\xNN` escape, or one which is not ASCII.
+    InvalidHexEscape,
+    /// Malformed `\u{...}` escape, or one which is not a valid [`char`].
+    InvalidUnicodeEscape,
+}
+
+impl Display for EscapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "unexpected end of string in escape sequence"),
+            Self::UnknownEscape => write!(f, "unknown escape sequence"),
+            Self::InvalidHexEscape => write!(f, "invalid hex escape sequence"),
+            Self::InvalidUnicodeEscape => write!(f, "invalid unicode escape sequence"),
+        }
+    }
+}
+
+/// A possible error when decoding UTF-16 bytes with
+/// [`from_utf16le_bytes()`](crate::string::ImString::from_utf16le_bytes) or
+/// [`from_utf16be_bytes()`](crate::string::ImString::from_utf16be_bytes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Utf16BytesError {
+    /// Number of bytes is odd, so they cannot be split into UTF-16 code units.
+    OddLength,
+    /// Code units are not valid UTF-16, because they contain an unpaired surrogate.
+    Invalid(DecodeUtf16Error),
+}
+
+impl Display for Utf16BytesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Self::OddLength => write!(f, "odd number of bytes in UTF-16 data"),
+            Self::Invalid(error) => write!(f, "invalid UTF-16: {error}"),
+        }
+    }
+}
+
 #[test]
+#[allow(clippy::clone_on_copy, unused_must_use)]
 fn slice_error_traits() {
     use SliceError::*;
     let errors = [
@@ -51,3 +154,55 @@ fn slice_error_traits() {
         alloc::format!("{new}");
     }
 }
+
+#[test]
+#[allow(clippy::clone_on_copy)]
+fn escape_error_traits() {
+    use EscapeError::*;
+    let errors = [
+        UnexpectedEnd,
+        UnknownEscape,
+        InvalidHexEscape,
+        InvalidUnicodeEscape,
+    ];
+
+    for error in errors.into_iter() {
+        // implements clone
+        let new = error.clone();
+        // implements partial eq
+        assert_eq!(error, new);
+        // implements debug
+        let _ = alloc::format!("{error:?}");
+        // implements display
+        let _ = alloc::format!("{new}");
+    }
+}
+
+#[test]
+fn slice_error_detailed_display() {
+    let error = SliceErrorDetailed::new(SliceError::EndOutOfBounds, 2..10, 5);
+    assert_eq!(
+        alloc::format!("{error}"),
+        "end offset out of bounds: range 2..10 for string of length 5"
+    );
+    assert_eq!(error.kind(), SliceError::EndOutOfBounds);
+    assert_eq!(error.range(), 2..10);
+    assert_eq!(error.length(), 5);
+    assert_eq!(SliceError::from(error), SliceError::EndOutOfBounds);
+}
+
+#[test]
+fn utf16_bytes_error_display() {
+    let error = Utf16BytesError::OddLength;
+    assert_eq!(
+        alloc::format!("{error}"),
+        "odd number of bytes in UTF-16 data"
+    );
+    let surrogate = char::decode_utf16([0xD800]).next().unwrap().unwrap_err();
+    let error = Utf16BytesError::Invalid(surrogate.clone());
+    assert_eq!(
+        alloc::format!("{error}"),
+        "invalid UTF-16: unpaired surrogate found: d800"
+    );
+    assert_eq!(error, Utf16BytesError::Invalid(surrogate));
+}
@@ -0,0 +1,102 @@
+//! Fast content hashing for [`ImString`].
+//!
+//! This module is only available when the `fxhash` feature is enabled. It implements the FxHash
+//! algorithm used by the Rust compiler, which is very fast for short keys but is not resistant
+//! against collision attacks, so it should not be used for keys that come from untrusted input.
+use crate::data::Data;
+use crate::string::ImString;
+use alloc::string::String;
+
+/// Multiplication constant of FxHash.
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// Mixes a single word into the hash state.
+fn add_to_hash(hash: u64, word: u64) -> u64 {
+    (hash.rotate_left(5) ^ word).wrapping_mul(SEED)
+}
+
+impl<S: Data<String>> ImString<S> {
+    /// Computes a fast, non-cryptographic hash of the contents of this string.
+    ///
+    /// Equal strings always produce the same hash, regardless of how they are stored or sliced.
+    /// The value is stable across runs, but may change between versions of this crate. This is
+    /// useful for building custom maps or interners with hot string keys, where the default
+    /// hasher is too slow.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("hello, hello");
+    /// assert_eq!(string.slice(..5).content_fxhash(), string.slice(7..).content_fxhash());
+    /// assert_ne!(string.slice(..5).content_fxhash(), string.content_fxhash());
+    /// ```
+    pub fn content_fxhash(&self) -> u64 {
+        let bytes = self.as_bytes();
+        let mut chunks = bytes.chunks_exact(8);
+        let mut hash = 0;
+        for chunk in &mut chunks {
+            let mut word = [0; 8];
+            word.copy_from_slice(chunk);
+            hash = add_to_hash(hash, u64::from_le_bytes(word));
+        }
+        for byte in chunks.remainder() {
+            hash = add_to_hash(hash, u64::from(*byte));
+        }
+
+        // mix in the length, so that strings which only differ in trailing zero bytes differ
+        add_to_hash(hash, bytes.len() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::string::{ImString, Local, Threadsafe};
+
+    #[test]
+    fn test_content_fxhash_equal() {
+        let inputs = ["", "a", "hello", "hello, world", "\0", "\0\0", "ütf-8 ✓"];
+        for input in inputs {
+            let threadsafe: ImString<Threadsafe> = ImString::from(input);
+            let local: ImString<Local> = ImString::from(input);
+            assert_eq!(threadsafe.content_fxhash(), local.content_fxhash());
+            let sliced: ImString<Threadsafe> = ImString::from(alloc::format!("[{input}]"));
+            let sliced = sliced.slice(1..sliced.len() - 1);
+            assert_eq!(sliced.content_fxhash(), threadsafe.content_fxhash());
+        }
+    }
+
+    #[test]
+    fn test_content_fxhash_differs() {
+        let inputs = [
+            "",
+            "a",
+            "b",
+            "ab",
+            "ba",
+            "\0",
+            "\0\0",
+            "hello, world",
+            "hello, world!",
+        ];
+        for (i, a) in inputs.iter().enumerate() {
+            for b in &inputs[i + 1..] {
+                let a: ImString<Threadsafe> = ImString::from(*a);
+                let b: ImString<Threadsafe> = ImString::from(*b);
+                assert_ne!(a.content_fxhash(), b.content_fxhash(), "{a:?} and {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_content_fxhash_not_default_hasher() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let string: ImString<Threadsafe> = ImString::from("hello, world");
+        let mut hasher = DefaultHasher::new();
+        string.hash(&mut hasher);
+        assert_ne!(string.content_fxhash(), hasher.finish());
+    }
+}
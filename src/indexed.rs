@@ -0,0 +1,219 @@
+//! [`Indexed`] wrapper which caches the character and line counts of an [`ImString`].
+//!
+//! Counting the [`char`]s or lines of a string requires scanning all of it. For text where these
+//! counts are queried repeatedly, such as cursor calculations in an editor, the [`Indexed`]
+//! wrapper computes them once and keeps them up to date as the string is modified.
+use crate::data::Data;
+use crate::string::ImString;
+use alloc::string::String;
+use core::fmt::{Debug, Display, Error as FmtError, Formatter};
+use core::ops::Deref;
+
+/// [`ImString`] with cached character and line counts.
+///
+/// Reading the [`char_count()`](Indexed::char_count) and [`line_count()`](Indexed::line_count)
+/// is *O(1)*. The wrapper dereferences to the inner [`ImString`] for all read-only operations,
+/// but it only allows mutation through its own methods, which update the cached counts.
+///
+/// # Example
+///
+/// ```rust
+/// use imstr::{ImString, indexed::Indexed};
+///
+/// let mut string = Indexed::new(ImString::from("hello\nwörld"));
+/// assert_eq!(string.char_count(), 11);
+/// assert_eq!(string.line_count(), 2);
+///
+/// string.push_str("\n!");
+/// assert_eq!(string.char_count(), 13);
+/// assert_eq!(string.line_count(), 3);
+/// ```
+#[derive(Clone)]
+pub struct Indexed<S: Data<String>> {
+    /// Underlying string
+    string: ImString<S>,
+    /// Number of chars in string.
+    chars: usize,
+    /// Number of newline characters in string.
+    newlines: usize,
+}
+
+fn count_newlines(string: &str) -> usize {
+    string.bytes().filter(|byte| *byte == b'\n').count()
+}
+
+impl<S: Data<String>> Indexed<S> {
+    /// Wraps an [`ImString`], computing its character and line counts.
+    ///
+    /// This is an *O(n)* operation, as it needs to scan the whole string.
+    pub fn new(string: ImString<S>) -> Self {
+        Indexed {
+            chars: string.chars().count(),
+            newlines: count_newlines(&string),
+            string,
+        }
+    }
+
+    /// Returns the number of [`char`]s in the string.
+    ///
+    /// This is the same as `string.chars().count()`, but *O(1)*.
+    pub fn char_count(&self) -> usize {
+        self.chars
+    }
+
+    /// Returns the number of lines in the string.
+    ///
+    /// This is the same as `string.lines().count()`, but *O(1)*.
+    pub fn line_count(&self) -> usize {
+        let unterminated = !self.string.is_empty() && !self.string.ends_with('\n');
+        self.newlines + usize::from(unterminated)
+    }
+
+    /// Returns a reference to the inner [`ImString`].
+    pub fn as_im_string(&self) -> &ImString<S> {
+        &self.string
+    }
+
+    /// Unwraps the inner [`ImString`], discarding the cached counts.
+    pub fn into_inner(self) -> ImString<S> {
+        self.string
+    }
+
+    /// Appends the given [`char`] to the end of the string.
+    pub fn push(&mut self, c: char) {
+        self.string.push(c);
+        self.chars += 1;
+        self.newlines += usize::from(c == '\n');
+    }
+
+    /// Appends the given string slice to the end of the string.
+    pub fn push_str(&mut self, string: &str) {
+        self.string.push_str(string);
+        self.chars += string.chars().count();
+        self.newlines += count_newlines(string);
+    }
+
+    /// Removes the last character from the string and returns it.
+    pub fn pop(&mut self) -> Option<char> {
+        let c = self.string.pop()?;
+        self.chars -= 1;
+        self.newlines -= usize::from(c == '\n');
+        Some(c)
+    }
+
+    /// Shortens the string to the specified length, in bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `length` does not lie on a [`char`] boundary.
+    pub fn truncate(&mut self, length: usize) {
+        if let Some(tail) = self.string.get(length..) {
+            self.chars -= tail.chars().count();
+            self.newlines -= count_newlines(tail);
+        }
+        self.string.truncate(length);
+    }
+
+    /// Truncates the string, removing all contents.
+    pub fn clear(&mut self) {
+        self.string.clear();
+        self.chars = 0;
+        self.newlines = 0;
+    }
+}
+
+impl<S: Data<String>> Deref for Indexed<S> {
+    type Target = ImString<S>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.string
+    }
+}
+
+impl<S: Data<String>> From<ImString<S>> for Indexed<S> {
+    fn from(string: ImString<S>) -> Self {
+        Indexed::new(string)
+    }
+}
+
+impl<S: Data<String>> From<Indexed<S>> for ImString<S> {
+    fn from(string: Indexed<S>) -> Self {
+        string.into_inner()
+    }
+}
+
+impl<S: Data<String>> Debug for Indexed<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        Debug::fmt(&self.string, f)
+    }
+}
+
+impl<S: Data<String>> Display for Indexed<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        Display::fmt(&self.string, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::{Arc, Cloned, Rc};
+    use alloc::boxed::Box;
+
+    fn assert_counts<S: Data<String>>(string: &Indexed<S>) {
+        assert_eq!(string.char_count(), string.chars().count());
+        assert_eq!(string.line_count(), string.as_str().lines().count());
+    }
+
+    fn test_counts<S: Data<String>>() {
+        for input in [
+            "",
+            "a",
+            "ü\n",
+            "\n\n",
+            "one\ntwo",
+            "one\r\ntwo\r\n",
+            "\u{1f600}",
+        ] {
+            let string: Indexed<S> = ImString::from(input).into();
+            assert_counts(&string);
+            let string: Indexed<S> = ImString::from(input).slice(..).into();
+            assert_counts(&string);
+        }
+    }
+
+    fn test_mutation<S: Data<String>>() {
+        let mut string: Indexed<S> = ImString::from("hello").into();
+        assert_counts(&string);
+        string.push_str("\nwörld");
+        assert_counts(&string);
+        string.push('\n');
+        assert_counts(&string);
+        string.push_str("\u{1f600}\n\n");
+        assert_counts(&string);
+        assert_eq!(string.pop(), Some('\n'));
+        assert_counts(&string);
+        string.truncate(9);
+        assert_eq!(string.as_str(), "hello\nwö");
+        assert_counts(&string);
+        string.truncate(100);
+        assert_counts(&string);
+        string.clear();
+        assert_counts(&string);
+        assert_eq!(string.pop(), None);
+        assert_counts(&string);
+    }
+
+    #[test]
+    fn test_all() {
+        test_counts::<Arc<String>>();
+        test_counts::<Rc<String>>();
+        test_counts::<Box<String>>();
+        test_counts::<Cloned<String>>();
+
+        test_mutation::<Arc<String>>();
+        test_mutation::<Rc<String>>();
+        test_mutation::<Box<String>>();
+        test_mutation::<Cloned<String>>();
+    }
+}
@@ -15,6 +15,8 @@ extern crate alloc;
 
 pub mod data;
 pub mod error;
+pub mod indexed;
+pub mod static_str;
 pub mod string;
 
 /// Thread-safe immutable string.
@@ -31,8 +33,32 @@ pub mod string;
 /// Any type which implements the [Data](data::Data) trait can be used as backing stores.
 pub type ImString = string::ImString<string::Threadsafe>;
 
+#[cfg(feature = "serde")]
+mod serde;
+
 #[cfg(feature = "peg")]
 mod peg;
 
 #[cfg(feature = "nom")]
 mod nom;
+
+#[cfg(feature = "proptest")]
+pub mod proptest;
+
+#[cfg(feature = "std")]
+pub mod reader;
+
+#[cfg(feature = "similarity")]
+mod similarity;
+
+#[cfg(feature = "memchr")]
+pub mod memchr;
+
+#[cfg(feature = "natural-sort")]
+mod natural;
+
+#[cfg(feature = "fxhash")]
+mod fxhash;
+
+#[cfg(feature = "bytes")]
+mod bytes;
@@ -0,0 +1,170 @@
+//! Byte search for [`ImString`] accelerated with [memchr](https://docs.rs/memchr).
+//!
+//! This module is only available when the `memchr` feature is enabled. Searching for a single
+//! byte is a common operation when parsing ASCII-delimited data, and the `memchr` crate uses SIMD
+//! instructions where available to do it much faster than a naive loop.
+use crate::data::Data;
+use crate::string::ImString;
+use alloc::string::String;
+
+impl<S: Data<String>> ImString<S> {
+    /// Returns the byte offset of the first occurrence of `byte` in this string.
+    ///
+    /// The offset is relative to this string. If `byte` is ASCII, the offset always lies on
+    /// a [`char`] boundary, so it can be used to slice the string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("key=value=other");
+    /// assert_eq!(string.find_byte(b'='), Some(3));
+    /// assert_eq!(string.find_byte(b'!'), None);
+    /// ```
+    pub fn find_byte(&self, byte: u8) -> Option<usize> {
+        memchr::memchr(byte, self.as_bytes())
+    }
+
+    /// Returns the byte offset of the last occurrence of `byte` in this string.
+    ///
+    /// The offset is relative to this string. If `byte` is ASCII, the offset always lies on
+    /// a [`char`] boundary, so it can be used to slice the string.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("key=value=other");
+    /// assert_eq!(string.rfind_byte(b'='), Some(9));
+    /// assert_eq!(string.rfind_byte(b'!'), None);
+    /// ```
+    pub fn rfind_byte(&self, byte: u8) -> Option<usize> {
+        memchr::memrchr(byte, self.as_bytes())
+    }
+
+    /// Returns an iterator over the parts of this string separated by `byte`.
+    ///
+    /// This behaves like [`split()`](str::split) with a single ASCII [`char`] as pattern, but
+    /// uses `memchr` to find the separators and yields [`ImString`]s which share the backing
+    /// string with this one. This is the common case when splitting CSV or TSV data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte` is not ASCII, because splitting on it could produce invalid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("name\tage\t\tcity");
+    /// let parts: Vec<ImString> = string.split_byte(b'\t').collect();
+    /// assert_eq!(parts, ["name", "age", "", "city"]);
+    /// ```
+    pub fn split_byte(&self, byte: u8) -> SplitByte<S> {
+        assert!(byte.is_ascii(), "split byte {byte:#04x} is not ASCII");
+        SplitByte {
+            string: Some(self.clone()),
+            byte,
+        }
+    }
+}
+
+/// Iterator over the parts of an [`ImString`] separated by a single byte.
+///
+/// This is returned by [`ImString::split_byte()`].
+#[derive(Clone, Debug)]
+pub struct SplitByte<S: Data<String>> {
+    string: Option<ImString<S>>,
+    byte: u8,
+}
+
+impl<S: Data<String>> Iterator for SplitByte<S> {
+    type Item = ImString<S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let string = self.string.take()?;
+        match memchr::memchr(self.byte, string.as_bytes()) {
+            Some(index) => {
+                // SAFETY: the byte is ASCII, so the bytes before and after it lie on char
+                // boundaries.
+                let (part, rest) = unsafe {
+                    (
+                        string.slice_unchecked(..index),
+                        string.slice_unchecked(index + 1..),
+                    )
+                };
+                self.string = Some(rest);
+                Some(part)
+            }
+            None => Some(string),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::data::Data;
+    use crate::ImString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_find_byte() {
+        let string = ImString::from("a,b,c");
+        assert_eq!(string.find_byte(b','), Some(1));
+        assert_eq!(string.rfind_byte(b','), Some(3));
+        assert_eq!(string.find_byte(b'a'), Some(0));
+        assert_eq!(string.rfind_byte(b'a'), Some(0));
+        assert_eq!(string.find_byte(b'c'), Some(4));
+        assert_eq!(string.rfind_byte(b'c'), Some(4));
+        assert_eq!(string.find_byte(b';'), None);
+        assert_eq!(string.rfind_byte(b';'), None);
+        assert_eq!(ImString::new().find_byte(b','), None);
+        assert_eq!(ImString::new().rfind_byte(b','), None);
+    }
+
+    #[test]
+    fn test_find_byte_slice() {
+        let string = ImString::from("a,b,c,d").slice(2..5);
+        assert_eq!(string, "b,c");
+        assert_eq!(string.find_byte(b','), Some(1));
+        assert_eq!(string.rfind_byte(b','), Some(1));
+        assert_eq!(string.find_byte(b'a'), None);
+        assert_eq!(string.rfind_byte(b'd'), None);
+    }
+
+    #[test]
+    fn test_find_byte_matches_find() {
+        let string = ImString::from("über,straße;\tend\n");
+        for byte in [b',', b';', b'\t', b'\n', b'e', b'x'] {
+            assert_eq!(string.find_byte(byte), string.find(char::from(byte)));
+            assert_eq!(string.rfind_byte(byte), string.rfind(char::from(byte)));
+        }
+    }
+
+    #[test]
+    fn test_split_byte() {
+        let inputs = ["", ",", "a", "a,b,c", ",a,,b,", "ä,ö,,ü", "no separator"];
+        for input in inputs {
+            let string = ImString::from(input);
+            let parts: Vec<ImString> = string.split_byte(b',').collect();
+            let expected: Vec<&str> = input.split(',').collect();
+            assert_eq!(parts, expected);
+        }
+    }
+
+    #[test]
+    fn test_split_byte_shares() {
+        let string = ImString::from("id,name\n1,ferris").slice(8..);
+        let parts: Vec<ImString> = string.split_byte(b',').collect();
+        assert_eq!(parts, ["1", "ferris"]);
+        for part in &parts {
+            assert!(part.raw_string().ptr_eq(&string.raw_string()));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "is not ASCII")]
+    fn test_split_byte_non_ascii() {
+        let _ = ImString::from("abc").split_byte(0xC3);
+    }
+}
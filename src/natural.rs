@@ -0,0 +1,126 @@
+//! Natural sort order for [`ImString`].
+//!
+//! This module is only available when the `natural-sort` feature is enabled. It does not change
+//! the [`Ord`] implementation of [`ImString`], which stays lexicographic.
+use crate::data::Data;
+use crate::string::ImString;
+use alloc::string::String;
+use core::cmp::Ordering;
+
+impl<S: Data<String>> ImString<S> {
+    /// Compares this string with `other` using natural sort order.
+    ///
+    /// Runs of ASCII digits are compared by their numeric value rather than digit by digit, so
+    /// that `"file2"` sorts before `"file10"`. Everything else is compared lexicographically.
+    /// Strings which only differ in leading zeros, such as `"01"` and `"1"`, fall back to the
+    /// lexicographic order, so that this is a total order which agrees with [`Eq`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let mut files = vec![
+    ///     ImString::from("file10"),
+    ///     ImString::from("file2"),
+    ///     ImString::from("file1"),
+    /// ];
+    /// files.sort_by(|a, b| a.natural_cmp(b));
+    /// assert_eq!(files, ["file1", "file2", "file10"]);
+    /// ```
+    pub fn natural_cmp(&self, other: &ImString<S>) -> Ordering {
+        natural_cmp(self.as_bytes(), other.as_bytes()).then_with(|| self.cmp(other))
+    }
+}
+
+/// Returns the length of the run of ASCII digits at the start of `bytes`.
+fn digits(bytes: &[u8]) -> usize {
+    bytes
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit())
+        .count()
+}
+
+/// Compares two runs of ASCII digits by their numeric value.
+fn number_cmp(left: &[u8], right: &[u8]) -> Ordering {
+    let left = &left[left.iter().take_while(|byte| **byte == b'0').count()..];
+    let right = &right[right.iter().take_while(|byte| **byte == b'0').count()..];
+    left.len().cmp(&right.len()).then_with(|| left.cmp(right))
+}
+
+/// Compares byte strings in natural order.
+///
+/// Digits are always ASCII, so working on the UTF-8 encoded bytes gives the same result as
+/// working on chars.
+fn natural_cmp(mut left: &[u8], mut right: &[u8]) -> Ordering {
+    while let (Some(a), Some(b)) = (left.first(), right.first()) {
+        let (ordering, left_len, right_len) = if a.is_ascii_digit() && b.is_ascii_digit() {
+            let left_len = digits(left);
+            let right_len = digits(right);
+            let ordering = number_cmp(&left[..left_len], &right[..right_len]);
+            (ordering, left_len, right_len)
+        } else {
+            (a.cmp(b), 1, 1)
+        };
+
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+
+        left = &left[left_len..];
+        right = &right[right_len..];
+    }
+
+    left.len().cmp(&right.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ImString;
+    use alloc::vec::Vec;
+    use core::cmp::Ordering;
+
+    fn sorted(input: &[&str]) -> Vec<ImString> {
+        let mut strings: Vec<ImString> = input.iter().copied().map(ImString::from).collect();
+        strings.sort_by(|a, b| a.natural_cmp(b));
+        strings
+    }
+
+    #[test]
+    fn test_natural_sort() {
+        assert_eq!(
+            sorted(&["file1", "file10", "file2"]),
+            ["file1", "file2", "file10"]
+        );
+        assert_eq!(
+            sorted(&["v1.10", "v1.9", "v1.2", "v10.0"]),
+            ["v1.2", "v1.9", "v1.10", "v10.0"]
+        );
+        assert_eq!(sorted(&["b", "a10", "a", "a9"]), ["a", "a9", "a10", "b"]);
+        assert_eq!(sorted(&["10", "9", "", "x"]), ["", "9", "10", "x"]);
+    }
+
+    #[test]
+    fn test_natural_cmp() {
+        let cmp = |a: &str, b: &str| ImString::from(a).natural_cmp(&ImString::from(b));
+        assert_eq!(cmp("file2", "file10"), Ordering::Less);
+        assert_eq!(cmp("file10", "file2"), Ordering::Greater);
+        assert_eq!(cmp("file10", "file10"), Ordering::Equal);
+        assert_eq!(cmp("file", "file1"), Ordering::Less);
+        assert_eq!(cmp("file007", "file7"), "file007".cmp("file7"));
+        assert_eq!(cmp("file007", "file8"), Ordering::Less);
+        assert_eq!(cmp("über2", "über10"), Ordering::Less);
+        assert_eq!(cmp("a2b", "a2a"), Ordering::Greater);
+        assert_eq!(
+            cmp("99999999999999999999999", "100000000000000000000000"),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_default_ord_unchanged() {
+        let strings = sorted(&["file1", "file10", "file2"]);
+        let mut lexicographic = strings.clone();
+        lexicographic.sort();
+        assert_eq!(lexicographic, ["file1", "file10", "file2"]);
+    }
+}
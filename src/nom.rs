@@ -221,6 +221,17 @@ fn test_input_iter() {
     });
 }
 
+/// Splitting input at the position where a predicate matches.
+///
+/// This mirrors the implementation for `&str`, including the distinction between streaming
+/// and complete parsing:
+///
+/// - `split_at_position()` and `split_at_position1()` are used by nom's `streaming` parsers. If
+///   the predicate never matches, the input might continue after the end of the current string,
+///   so they signal [`Err::Incomplete`] rather than consuming everything.
+/// - `split_at_position_complete()` and `split_at_position1_complete()` are used by nom's
+///   `complete` parsers. They treat the end of the string as the end of the input, and consume
+///   everything if the predicate never matches.
 impl<S: Data<String>> InputTakeAtPosition for ImString<S> {
     type Item = char;
 
@@ -379,9 +390,64 @@ fn test_input_take_at_position() {
     });
 }
 
+#[test]
+fn test_streaming_parsers() {
+    use nom::bytes::streaming::{tag, take_while, take_while1};
+    use nom::character::streaming::{alpha1, digit1, space0};
+
+    fn test<S: Data<String>>() {
+        let string = ImString::<S>::from("abc123");
+
+        // token continues until the end of input, might continue
+        let result: IResult<_, _, ()> = take_while(|c: char| c.is_alphanumeric())(string.clone());
+        assert_eq!(result, Err(Err::Incomplete(Needed::new(1))));
+        let result: IResult<_, _, ()> = take_while1(|c: char| c.is_alphanumeric())(string.clone());
+        assert_eq!(result, Err(Err::Incomplete(Needed::new(1))));
+        let result: IResult<_, _, ()> = digit1(string.slice(3..));
+        assert_eq!(result, Err(Err::Incomplete(Needed::new(1))));
+
+        // token ends before the end of input
+        let result: IResult<_, _, ()> = alpha1(string.clone());
+        let (rest, token) = result.unwrap();
+        assert_eq!(token, "abc");
+        assert_eq!(rest, "123");
+
+        // empty token is fine for take_while, but not for take_while1
+        let result: IResult<_, _, ()> = take_while(|c: char| c.is_alphabetic())(string.slice(3..));
+        let (rest, token) = result.unwrap();
+        assert_eq!(token, "");
+        assert_eq!(rest, "123");
+        let result: IResult<_, _, ()> = take_while1(|c: char| c.is_alphabetic())(string.slice(3..));
+        assert_eq!(result, Err(Err::Error(())));
+
+        // empty input is always incomplete
+        let result: IResult<_, _, ()> = space0(ImString::<S>::new());
+        assert_eq!(result, Err(Err::Incomplete(Needed::new(1))));
+
+        // streaming tag with input that ends mid-tag
+        let result: IResult<_, _, ()> = tag("abc123def")(string.clone());
+        assert_eq!(result, Err(Err::Incomplete(Needed::new(3))));
+    }
+
+    test::<std::sync::Arc<String>>();
+    test::<std::rc::Rc<String>>();
+    test::<std::boxed::Box<String>>();
+}
+
+/// Offset of `second` relative to `self`.
+///
+/// Nom only ever calls this with `second` being a suffix of `self`, meaning that it must start at
+/// or after the start of `self`. Calling it with the arguments the other way around is a bug, and
+/// triggers a debug assertion. In release builds, this returns `0` instead of underflowing.
 impl<S: Data<String>> Offset for ImString<S> {
     fn offset(&self, second: &Self) -> usize {
-        second.raw_offset().start - self.raw_offset().start
+        let start = self.raw_offset_ref().start;
+        let second_start = second.raw_offset_ref().start;
+        debug_assert!(
+            second_start >= start,
+            "offset(): second ({second_start}) starts before self ({start}), arguments reversed?"
+        );
+        second_start.saturating_sub(start)
     }
 }
 
@@ -401,6 +467,21 @@ fn test_offset() {
     });
 }
 
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "arguments reversed?")]
+fn test_offset_reversed() {
+    let string = ImString::<std::sync::Arc<String>>::from("hello");
+    string.slice(2..).offset(&string);
+}
+
+#[test]
+#[cfg(not(debug_assertions))]
+fn test_offset_reversed() {
+    let string = ImString::<std::sync::Arc<String>>::from("hello");
+    assert_eq!(string.slice(2..).offset(&string), 0);
+}
+
 impl<'a, S: Data<String>> Compare<&'a str> for ImString<S> {
     fn compare(&self, t: &'a str) -> CompareResult {
         self.as_str().compare(t)
@@ -482,6 +563,75 @@ fn test_compare_bytes() {
     });
 }
 
+impl<S: Data<String>, O: Data<String>> Compare<ImString<O>> for ImString<S> {
+    fn compare(&self, t: ImString<O>) -> CompareResult {
+        self.as_str().compare(t.as_str())
+    }
+
+    fn compare_no_case(&self, t: ImString<O>) -> CompareResult {
+        self.as_str().compare_no_case(t.as_str())
+    }
+}
+
+#[test]
+fn test_compare_im_string() {
+    fn test<S: Data<String>, O: Data<String>>() {
+        let string = ImString::<S>::from("");
+        assert_eq!(string.compare(ImString::<O>::from("")), CompareResult::Ok);
+        assert_eq!(
+            string.compare(ImString::<O>::from("err")),
+            CompareResult::Incomplete
+        );
+        assert_eq!(
+            string.compare_no_case(ImString::<O>::from("")),
+            CompareResult::Ok
+        );
+        assert_eq!(
+            string.compare_no_case(ImString::<O>::from("err")),
+            CompareResult::Incomplete
+        );
+
+        let string = ImString::<S>::from("string");
+        assert_eq!(
+            string.compare(ImString::<O>::from("string")),
+            CompareResult::Ok
+        );
+        assert_eq!(
+            string.compare(ImString::<O>::from("str")),
+            CompareResult::Ok
+        );
+        assert_eq!(
+            string.compare(ImString::<O>::from("string0")),
+            CompareResult::Incomplete
+        );
+        assert_eq!(
+            string.compare(ImString::<O>::from("var")),
+            CompareResult::Error
+        );
+        assert_eq!(
+            string.compare_no_case(ImString::<O>::from("STRING")),
+            CompareResult::Ok
+        );
+        assert_eq!(
+            string.compare_no_case(ImString::<O>::from("STRING0")),
+            CompareResult::Incomplete
+        );
+        assert_eq!(
+            string.compare_no_case(ImString::<O>::from("VAR")),
+            CompareResult::Error
+        );
+
+        // tag is a slice of a larger string
+        let tag = ImString::<O>::from("a string here").slice(2..8);
+        assert_eq!(string.compare(tag), CompareResult::Ok);
+    }
+
+    test::<std::sync::Arc<String>, std::sync::Arc<String>>();
+    test::<std::sync::Arc<String>, std::rc::Rc<String>>();
+    test::<std::rc::Rc<String>, std::boxed::Box<String>>();
+    test::<std::boxed::Box<String>, std::sync::Arc<String>>();
+}
+
 impl<'a, S: Data<String>> FindSubstring<&'a str> for ImString<S> {
     fn find_substring(&self, sub_string: &'a str) -> std::option::Option<usize> {
         self.as_str().find_substring(sub_string)
@@ -522,6 +672,10 @@ fn test_as_bytes() {
     });
 }
 
+/// Parses the string using [`FromStr`].
+///
+/// Like for `&str`, this does not trim surrounding whitespace. Use
+/// [`parse_trimmed()`](ImString::parse_trimmed) if the input may contain it.
 impl<S: Data<String>, R: FromStr> ParseTo<R> for ImString<S> {
     fn parse_to(&self) -> Option<R> {
         self.parse().ok()
@@ -42,3 +42,66 @@ impl<'input, S: Data<String>> ParseSlice<'input> for ImString<S> {
         self.slice(p1..p2)
     }
 }
+
+impl<S: Data<String>> ImString<S> {
+    /// Map a span of parser positions to a slice of this string.
+    ///
+    /// Positions reported by peg, for example through `position!()`, are byte offsets relative
+    /// to the start of this string. This turns a `(start, end)` pair of them into an [`ImString`]
+    /// that shares the backing string with the input, just like the `$()` slice operator does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the span is not valid for this string. Use [`try_slice`](ImString::try_slice)
+    /// to handle invalid spans.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// peg::parser! {
+    ///     grammar words() for imstr::ImString {
+    ///         pub rule word() -> (usize, usize) =
+    ///             start:position!() ['a'..='z']+ end:position!() { (start, end) }
+    ///     }
+    /// }
+    ///
+    /// let input = imstr::ImString::from("hello");
+    /// let (start, end) = words::word(&input).unwrap();
+    /// assert_eq!(input.span(start, end), "hello");
+    /// ```
+    pub fn span(&self, start: usize, end: usize) -> Self {
+        self.slice(start..end)
+    }
+
+    /// Match a string literal at the given position, ignoring ASCII case.
+    ///
+    /// This works like [`ParseLiteral::parse_string_literal`], except that it compares the input
+    /// to `literal` using [`eq_ignore_ascii_case`](slice::eq_ignore_ascii_case). Grammars
+    /// cannot express case-insensitive literals directly, but this can be called from a custom
+    /// expression inside a helper rule.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// peg::parser! {
+    ///     grammar query() for imstr::ImString {
+    ///         rule keyword(literal: &'static str) =
+    ///             #{|input, pos| input.parse_string_literal_ci(pos, literal)}
+    ///
+    ///         pub rule select() = keyword("select") " " ['*']
+    ///     }
+    /// }
+    ///
+    /// assert!(query::select(&"SELECT *".into()).is_ok());
+    /// assert!(query::select(&"select *".into()).is_ok());
+    /// ```
+    pub fn parse_string_literal_ci(&self, pos: usize, literal: &str) -> RuleResult<()> {
+        let end = pos + literal.len();
+        match self.as_bytes().get(pos..end) {
+            Some(slice) if slice.eq_ignore_ascii_case(literal.as_bytes()) => {
+                RuleResult::Matched(end, ())
+            }
+            _ => RuleResult::Failed,
+        }
+    }
+}
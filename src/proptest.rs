@@ -0,0 +1,41 @@
+//! Strategies for generating [`ImString`] values with [proptest](https://docs.rs/proptest).
+//!
+//! This module is only available when the `proptest` feature is enabled. It allows writing
+//! property tests which take [`ImString`] values as input. The generated strings are not just
+//! freshly allocated, but also include sliced views into a larger backing string, which makes
+//! sure that properties hold regardless of the offset.
+use crate::ImString;
+use alloc::{string::String, vec::Vec};
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+/// Strategy which generates arbitrary [`ImString`] values, including sliced views.
+///
+/// Every generated value is a slice of an arbitrary backing string, with both the start and the
+/// end of the slice lying on a [`char`] boundary. This means the generated value may share its
+/// backing string with data that is outside of its view.
+///
+/// # Example
+///
+/// ```rust
+/// use imstr::proptest::im_string_strategy;
+/// use proptest::prelude::*;
+///
+/// proptest! {
+///     fn slice_roundtrip(string in im_string_strategy()) {
+///         prop_assert_eq!(string.slice(..), string.as_str());
+///     }
+/// }
+/// # slice_roundtrip();
+/// ```
+pub fn im_string_strategy() -> impl Strategy<Value = ImString> {
+    (any::<String>(), any::<Index>(), any::<Index>()).prop_map(|(string, start, end)| {
+        let string = ImString::from(string);
+        let boundaries: Vec<usize> = (0..=string.len())
+            .filter(|index| string.is_char_boundary(*index))
+            .collect();
+        let start = boundaries[start.index(boundaries.len())];
+        let end = boundaries[end.index(boundaries.len())];
+        string.slice(start.min(end)..start.max(end))
+    })
+}
@@ -0,0 +1,182 @@
+//! Chunked line reader producing [`ImString`]s.
+//!
+//! This module is only available when the `std` feature is enabled. Reading a whole file with
+//! [`ImString::read_to_imstring()`] keeps all of it in memory at once. For large inputs, the
+//! [`ImStringReader`] reads in chunks instead, so that only the lines which are still referenced
+//! are kept alive.
+use crate::data::Data;
+use crate::string::{ImString, Threadsafe};
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind, Read, Result};
+
+/// Default size of the chunks read by [`ImStringReader::new()`].
+pub const DEFAULT_CHUNK_SIZE: usize = 8 * 1024;
+
+/// Reader which yields the lines of its input as [`ImString`]s.
+///
+/// The input is read in chunks of roughly the configured size. Every chunk is cut at its last
+/// newline and stored in its own backing string, which is shared by all of the lines in it. A
+/// line which is longer than the chunk size is read in full, so a chunk is always at least as
+/// long as the longest line in it.
+///
+/// Lines are split like [`str::lines()`]: they do not include the line terminator, which is
+/// either `\n` or `\r\n`, and a trailing empty line is not yielded.
+///
+/// # Example
+///
+/// ```rust
+/// # use imstr::ImString;
+/// use imstr::reader::ImStringReader;
+/// use std::io::Cursor;
+///
+/// let reader = ImStringReader::with_chunk_size(Cursor::new("first\nsecond\r\nthird"), 8);
+/// let lines: Vec<ImString> = reader.collect::<Result<_, _>>().unwrap();
+/// assert_eq!(lines, ["first", "second", "third"]);
+/// ```
+#[derive(Debug)]
+pub struct ImStringReader<R, S: Data<String> = Threadsafe> {
+    reader: R,
+    chunk_size: usize,
+    buffer: Vec<u8>,
+    lines: VecDeque<ImString<S>>,
+    done: bool,
+}
+
+impl<R: Read, S: Data<String>> ImStringReader<R, S> {
+    /// Creates a new reader which reads chunks of [`DEFAULT_CHUNK_SIZE`] bytes.
+    pub fn new(reader: R) -> Self {
+        Self::with_chunk_size(reader, DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Creates a new reader which reads chunks of `chunk_size` bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn with_chunk_size(reader: R, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be non-zero");
+        ImStringReader {
+            reader,
+            chunk_size,
+            buffer: Vec::new(),
+            lines: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Returns the underlying reader.
+    ///
+    /// Any data which has already been read, but not yet yielded, is lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Reads from the underlying reader until a complete chunk is available.
+    ///
+    /// Returns the chunk, or `None` if the input is exhausted.
+    fn read_chunk(&mut self) -> Result<Option<Vec<u8>>> {
+        loop {
+            let start = self.buffer.len();
+            self.buffer.resize(start + self.chunk_size, 0);
+            let result = self.reader.read(&mut self.buffer[start..]);
+            let count = *result.as_ref().unwrap_or(&0);
+            self.buffer.truncate(start + count);
+            match result {
+                Ok(0) if self.buffer.is_empty() => return Ok(None),
+                Ok(0) => return Ok(Some(std::mem::take(&mut self.buffer))),
+                Ok(_) => {}
+                Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+                Err(error) => return Err(error),
+            }
+
+            if let Some(position) = self.buffer[start..].iter().rposition(|byte| *byte == b'\n') {
+                let rest = self.buffer.split_off(start + position + 1);
+                return Ok(Some(std::mem::replace(&mut self.buffer, rest)));
+            }
+        }
+    }
+}
+
+impl<R: Read, S: Data<String>> Iterator for ImStringReader<R, S> {
+    type Item = Result<ImString<S>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.lines.is_empty() && !self.done {
+            let chunk = match self.read_chunk() {
+                Ok(Some(chunk)) => chunk,
+                Ok(None) => {
+                    self.done = true;
+                    break;
+                }
+                Err(error) => {
+                    self.done = true;
+                    return Some(Err(error));
+                }
+            };
+
+            // chunks are only ever cut after a newline, so they are valid UTF-8 on their own
+            let chunk = match String::from_utf8(chunk) {
+                Ok(chunk) => ImString::<S>::from_std_string(chunk),
+                Err(error) => {
+                    self.done = true;
+                    return Some(Err(Error::new(ErrorKind::InvalidData, error)));
+                }
+            };
+            self.lines.extend(chunk.line_ranges().map(|(line, _)| line));
+        }
+
+        self.lines.pop_front().map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::string::Local;
+    use std::io::Cursor;
+
+    const INPUT: &str = "first line\nsecond\r\n\na much longer line which spans chunks\nü\nlast";
+
+    #[test]
+    fn test_reader_lines() {
+        for chunk_size in [1, 2, 3, 7, 16, DEFAULT_CHUNK_SIZE] {
+            let reader = ImStringReader::with_chunk_size(Cursor::new(INPUT), chunk_size);
+            let lines: Vec<ImString<Threadsafe>> = reader.collect::<Result<_>>().unwrap();
+            let expected: Vec<&str> = INPUT.lines().collect();
+            assert_eq!(lines, expected, "chunk size {chunk_size}");
+        }
+    }
+
+    #[test]
+    fn test_reader_trailing_newline() {
+        for input in ["", "\n", "a\n", "a\n\n", "a\r\nb\r\n"] {
+            let reader = ImStringReader::with_chunk_size(Cursor::new(input), 2);
+            let lines: Vec<ImString<Local>> = reader.collect::<Result<_>>().unwrap();
+            let expected: Vec<&str> = input.lines().collect();
+            assert_eq!(lines, expected, "input {input:?}");
+        }
+    }
+
+    #[test]
+    fn test_reader_shares_chunk() {
+        let reader = ImStringReader::with_chunk_size(Cursor::new("a\nb\nc\nd\n"), 4);
+        let lines: Vec<ImString<Threadsafe>> = reader.collect::<Result<_>>().unwrap();
+        assert_eq!(lines, ["a", "b", "c", "d"]);
+        assert!(lines[0].raw_string().ptr_eq(&lines[1].raw_string()));
+        assert!(lines[2].raw_string().ptr_eq(&lines[3].raw_string()));
+        assert!(!lines[1].raw_string().ptr_eq(&lines[2].raw_string()));
+    }
+
+    #[test]
+    fn test_reader_invalid_utf8() {
+        let reader = ImStringReader::<_, Threadsafe>::new(Cursor::new(b"valid\ninvalid \xff\n"));
+        let error = reader.collect::<Result<Vec<_>>>().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk size must be non-zero")]
+    fn test_reader_zero_chunk_size() {
+        let _ = ImStringReader::<_, Threadsafe>::with_chunk_size(Cursor::new(""), 0);
+    }
+}
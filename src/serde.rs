@@ -0,0 +1,43 @@
+//! Serialization and deserialization of [`ImString`] with [serde](https://serde.rs).
+//!
+//! This module is only available when the `serde` feature is enabled. An [`ImString`] is
+//! serialized as a plain string, regardless of its backing type and of which part of the backing
+//! string it refers to. This means it can also be used as the key of a map.
+use crate::data::Data;
+use crate::string::ImString;
+use alloc::string::String;
+use core::fmt::{Formatter, Result as FmtResult};
+use core::marker::PhantomData;
+use serde::de::{Deserialize, Deserializer, Error, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+impl<S: Data<String>> Serialize for ImString<S> {
+    fn serialize<T: Serializer>(&self, serializer: T) -> Result<T::Ok, T::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Visitor which builds an [`ImString`], reusing owned strings where possible.
+struct ImStringVisitor<S>(PhantomData<S>);
+
+impl<'de, S: Data<String>> Visitor<'de> for ImStringVisitor<S> {
+    type Value = ImString<S>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> FmtResult {
+        formatter.write_str("a string")
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+        Ok(ImString::from(value))
+    }
+
+    fn visit_string<E: Error>(self, value: String) -> Result<Self::Value, E> {
+        Ok(ImString::from_std_string(value))
+    }
+}
+
+impl<'de, S: Data<String>> Deserialize<'de> for ImString<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_string(ImStringVisitor(PhantomData))
+    }
+}
@@ -0,0 +1,146 @@
+//! String similarity metrics for [`ImString`].
+//!
+//! This module is only available when the `similarity` feature is enabled. The metrics operate
+//! on [`char`]s, not on bytes or graphemes.
+use crate::data::Data;
+use crate::string::ImString;
+use alloc::{string::String, vec, vec::Vec};
+
+impl<S: Data<String>> ImString<S> {
+    /// Computes the Levenshtein distance between this string and `other`.
+    ///
+    /// This is the minimum number of single-[`char`] insertions, deletions or substitutions
+    /// needed to turn one string into the other.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("kitten");
+    /// assert_eq!(string.levenshtein("sitting"), 3);
+    /// assert_eq!(string.levenshtein("kitten"), 0);
+    /// ```
+    pub fn levenshtein(&self, other: &str) -> usize {
+        let other: Vec<char> = other.chars().collect();
+        let mut previous: Vec<usize> = (0..=other.len()).collect();
+        let mut current = vec![0; other.len() + 1];
+
+        for (i, a) in self.chars().enumerate() {
+            current[0] = i + 1;
+            for (j, b) in other.iter().enumerate() {
+                let substitution = previous[j] + usize::from(a != *b);
+                current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            }
+            core::mem::swap(&mut previous, &mut current);
+        }
+
+        previous[other.len()]
+    }
+
+    /// Computes the Jaro-Winkler similarity between this string and `other`.
+    ///
+    /// The result is between `0.0` (no similarity) and `1.0` (identical strings). Strings that
+    /// share a common prefix of up to four [`char`]s are rated as more similar.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("martha");
+    /// assert!((string.jaro_winkler("marhta") - 0.961).abs() < 0.001);
+    /// assert_eq!(string.jaro_winkler("martha"), 1.0);
+    /// ```
+    pub fn jaro_winkler(&self, other: &str) -> f64 {
+        let left: Vec<char> = self.chars().collect();
+        let right: Vec<char> = other.chars().collect();
+        let jaro = jaro(&left, &right);
+        let prefix = left
+            .iter()
+            .zip(right.iter())
+            .take_while(|(a, b)| a == b)
+            .take(4)
+            .count();
+        jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+    }
+}
+
+fn jaro(left: &[char], right: &[char]) -> f64 {
+    if left.is_empty() && right.is_empty() {
+        return 1.0;
+    }
+    if left.is_empty() || right.is_empty() {
+        return 0.0;
+    }
+
+    let window = (left.len().max(right.len()) / 2).saturating_sub(1);
+    let mut left_matched = vec![false; left.len()];
+    let mut right_matched = vec![false; right.len()];
+    let mut matches = 0;
+    for (i, a) in left.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(right.len());
+        for j in start..end {
+            if !right_matched[j] && right[j] == *a {
+                left_matched[i] = true;
+                right_matched[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let left_chars = left.iter().zip(left_matched).filter(|(_, m)| *m);
+    let right_chars = right.iter().zip(right_matched).filter(|(_, m)| *m);
+    let transpositions = left_chars
+        .zip(right_chars)
+        .filter(|((a, _), (b, _))| a != b)
+        .count();
+
+    let matches = matches as f64;
+    let transpositions = (transpositions / 2) as f64;
+    (matches / left.len() as f64
+        + matches / right.len() as f64
+        + (matches - transpositions) / matches)
+        / 3.0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ImString;
+
+    #[test]
+    fn test_levenshtein() {
+        let string = ImString::from("kitten");
+        assert_eq!(string.levenshtein("sitting"), 3);
+        assert_eq!(string.levenshtein("kitten"), 0);
+        assert_eq!(string.levenshtein(""), 6);
+        assert_eq!(ImString::new().levenshtein("abc"), 3);
+        assert_eq!(ImString::new().levenshtein(""), 0);
+        assert_eq!(ImString::from("flaw").levenshtein("lawn"), 2);
+        assert_eq!(ImString::from("über").levenshtein("uber"), 1);
+        assert_eq!(
+            ImString::from("a kitten").slice(2..).levenshtein("mitten"),
+            1
+        );
+    }
+
+    #[test]
+    fn test_jaro_winkler() {
+        fn assert_close(value: f64, expected: f64) {
+            assert!((value - expected).abs() < 0.001, "{value} != {expected}");
+        }
+
+        let string = ImString::from("martha");
+        assert_eq!(string.jaro_winkler("martha"), 1.0);
+        assert_close(string.jaro_winkler("marhta"), 0.961);
+        assert_close(ImString::from("dwayne").jaro_winkler("duane"), 0.84);
+        assert_close(ImString::from("dixon").jaro_winkler("dicksonx"), 0.813);
+        assert_eq!(ImString::from("abc").jaro_winkler("xyz"), 0.0);
+        assert_eq!(ImString::from("abc").jaro_winkler(""), 0.0);
+        assert_eq!(ImString::new().jaro_winkler(""), 1.0);
+    }
+}
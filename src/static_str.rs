@@ -0,0 +1,163 @@
+//! [`StaticImStr`] type for strings which are known at compile time.
+//!
+//! An [`ImString`] always owns its backing, which has to be allocated by [`Data::new()`], so it
+//! cannot be created in a `const` context. A [`StaticImStr`] instead borrows a `&'static str`,
+//! which means it can be declared as a `const` or `static` and converted into an [`ImString`]
+//! when needed.
+use crate::data::Data;
+use crate::string::ImString;
+use alloc::string::String;
+use core::fmt::{Display, Error as FmtError, Formatter};
+use core::ops::Deref;
+
+/// String backed by a `&'static str`, which can be created in a `const` context.
+///
+/// # Example
+///
+/// ```rust
+/// use imstr::{static_str::StaticImStr, ImString};
+///
+/// static GREETING: StaticImStr = StaticImStr::from_static_const("hello");
+///
+/// let string: ImString = GREETING.into();
+/// assert_eq!(string, "hello");
+/// ```
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StaticImStr {
+    string: &'static str,
+}
+
+impl StaticImStr {
+    /// Creates a new [`StaticImStr`] from a string literal.
+    ///
+    /// This is a `const fn`, so it can be used to initialize `const` and `static` items.
+    pub const fn from_static_const(string: &'static str) -> Self {
+        StaticImStr { string }
+    }
+
+    /// Creates a new, empty [`StaticImStr`].
+    ///
+    /// This is the same as [`StaticImStr::default()`], but can be used in a `const` context.
+    pub const fn new() -> Self {
+        StaticImStr { string: "" }
+    }
+
+    /// Returns the string as a `&'static str`.
+    pub const fn as_str(&self) -> &'static str {
+        self.string
+    }
+
+    /// Returns the length of the string, in bytes.
+    pub const fn len(&self) -> usize {
+        self.string.len()
+    }
+
+    /// Returns `true` if the string has a length of zero.
+    pub const fn is_empty(&self) -> bool {
+        self.string.is_empty()
+    }
+
+    /// Converts this string into an [`ImString`].
+    ///
+    /// This allocates a new backing string.
+    pub fn to_im_string<S: Data<String>>(&self) -> ImString<S> {
+        ImString::from(self.string)
+    }
+}
+
+impl<S: Data<String>> From<StaticImStr> for ImString<S> {
+    fn from(string: StaticImStr) -> Self {
+        string.to_im_string()
+    }
+}
+
+impl Deref for StaticImStr {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.string
+    }
+}
+
+impl Display for StaticImStr {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        Display::fmt(self.string, formatter)
+    }
+}
+
+impl PartialEq<str> for StaticImStr {
+    fn eq(&self, other: &str) -> bool {
+        self.string == other
+    }
+}
+
+impl PartialEq<&str> for StaticImStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.string == *other
+    }
+}
+
+impl PartialEq<StaticImStr> for str {
+    fn eq(&self, other: &StaticImStr) -> bool {
+        self == other.string
+    }
+}
+
+impl PartialEq<StaticImStr> for &str {
+    fn eq(&self, other: &StaticImStr) -> bool {
+        *self == other.string
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::string::{Local, Threadsafe};
+
+    const EMPTY: StaticImStr = StaticImStr::from_static_const("");
+    static GREETING: StaticImStr = StaticImStr::from_static_const("hello, wörld");
+    const GREETING_LEN: usize = GREETING.len();
+
+    #[test]
+    fn test_const() {
+        assert!(EMPTY.is_empty());
+        assert_eq!(EMPTY.as_str(), "");
+        assert_eq!(GREETING.as_str(), "hello, wörld");
+        assert_eq!(GREETING_LEN, 13);
+        assert!(!GREETING.is_empty());
+    }
+
+    #[test]
+    fn test_to_im_string() {
+        let string: ImString<Threadsafe> = GREETING.to_im_string();
+        assert_eq!(string, "hello, wörld");
+        let string: ImString<Local> = GREETING.into();
+        assert_eq!(string, "hello, wörld");
+        let string: ImString<Threadsafe> = EMPTY.into();
+        assert_eq!(string, "");
+    }
+
+    #[test]
+    fn test_default() {
+        const DEFAULT: StaticImStr = StaticImStr::new();
+        let string = StaticImStr::default();
+        assert!(string.is_empty());
+        assert_eq!(string, "");
+        assert_eq!("", string);
+        assert_eq!(string, EMPTY);
+        assert_eq!(string, DEFAULT);
+        assert_ne!(string, GREETING);
+    }
+
+    #[test]
+    fn test_traits() {
+        assert_eq!(GREETING, "hello, wörld");
+        assert_eq!(*"hello, wörld", GREETING);
+        assert_ne!(GREETING, "hello");
+        assert_eq!(alloc::format!("{GREETING}"), "hello, wörld");
+        assert_eq!(alloc::format!("{GREETING:>13}"), " hello, wörld");
+        assert!(GREETING.starts_with("hello"));
+        assert_eq!(GREETING.chars().count(), 12);
+        assert!(EMPTY < GREETING);
+    }
+}
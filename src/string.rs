@@ -3,12 +3,15 @@ use crate::data::Data;
 use crate::error::*;
 use alloc::{
     borrow::Cow,
+    boxed::Box,
+    collections::BTreeSet,
     rc::Rc,
     string::{String, ToString},
     sync::Arc,
     vec::Vec,
 };
 use core::{
+    any::Any,
     borrow::{Borrow, BorrowMut},
     cmp::Ordering,
     convert::{AsMut, AsRef, Infallible},
@@ -22,9 +25,7 @@ use core::{
     str::FromStr,
 };
 #[cfg(feature = "std")]
-use std::{ffi::OsStr, net::ToSocketAddrs, path::Path};
-#[cfg(feature = "serde")]
-use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, ffi::OsStr, io::Read, net::ToSocketAddrs, path::Path};
 
 /// Threadsafe shared storage for string.
 pub type Threadsafe = Arc<String>;
@@ -66,7 +67,6 @@ pub type Local = Rc<String>;
 /// assert_eq!(string_slice, "hello");
 /// ```
 #[derive(Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ImString<S: Data<String>> {
     /// Underlying string
     string: S,
@@ -93,6 +93,90 @@ fn try_slice_offset(current: &[u8], candidate: &[u8]) -> Option<Range<usize>> {
     Some(offset_start..offset_end)
 }
 
+/// Panics with a message describing why slicing with `bounds` failed.
+#[cold]
+#[inline(never)]
+#[track_caller]
+fn slice_failed(bounds: (Bound<usize>, Bound<usize>), length: usize, error: SliceError) -> ! {
+    let start = match bounds.0 {
+        Bound::Included(value) => Some(value),
+        Bound::Excluded(value) => Some(value.saturating_add(1)),
+        Bound::Unbounded => None,
+    };
+    let (inclusive, end) = match bounds.1 {
+        Bound::Included(value) => ("=", Some(value)),
+        Bound::Excluded(value) => ("", Some(value)),
+        Bound::Unbounded => ("", None),
+    };
+    panic!(
+        "slice range {}..{inclusive}{} is invalid for ImString of length {length}: {error}",
+        OptionalIndex(start),
+        OptionalIndex(end),
+    );
+}
+
+/// Decodes a single escape sequence, after the backslash has been consumed.
+fn decode_escape(chars: &mut core::str::Chars<'_>) -> Result<char, EscapeError> {
+    let c = match chars.next().ok_or(EscapeError::UnexpectedEnd)? {
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        '0' => '\0',
+        c @ ('\\' | '\'' | '"') => c,
+        'x' => {
+            let mut value = 0;
+            for _ in 0..2 {
+                let digit = chars.next().ok_or(EscapeError::UnexpectedEnd)?;
+                let digit = digit.to_digit(16).ok_or(EscapeError::InvalidHexEscape)?;
+                value = value * 16 + digit;
+            }
+            if value > 0x7f {
+                return Err(EscapeError::InvalidHexEscape);
+            }
+            char::from(value as u8)
+        }
+        'u' => {
+            if chars.next().ok_or(EscapeError::UnexpectedEnd)? != '{' {
+                return Err(EscapeError::InvalidUnicodeEscape);
+            }
+            let mut value = 0;
+            let mut digits = 0;
+            loop {
+                let digit = chars.next().ok_or(EscapeError::UnexpectedEnd)?;
+                if digit == '}' {
+                    break;
+                }
+                let digit = digit
+                    .to_digit(16)
+                    .ok_or(EscapeError::InvalidUnicodeEscape)?;
+                digits += 1;
+                if digits > 6 {
+                    return Err(EscapeError::InvalidUnicodeEscape);
+                }
+                value = value * 16 + digit;
+            }
+            if digits == 0 {
+                return Err(EscapeError::InvalidUnicodeEscape);
+            }
+            char::from_u32(value).ok_or(EscapeError::InvalidUnicodeEscape)?
+        }
+        _ => return Err(EscapeError::UnknownEscape),
+    };
+    Ok(c)
+}
+
+/// Displays an index if it is present, and nothing otherwise.
+struct OptionalIndex(Option<usize>);
+
+impl Display for OptionalIndex {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        match self.0 {
+            Some(index) => write!(formatter, "{index}"),
+            None => Ok(()),
+        }
+    }
+}
+
 impl<S: Data<String>> ImString<S> {
     /// Returns a byte slice of this string's contents.
     ///
@@ -136,11 +220,53 @@ impl<S: Data<String>> ImString<S> {
     /// ```
     pub fn from_std_string(string: String) -> Self {
         ImString {
-            offset: 0..string.as_bytes().len(),
+            offset: 0..string.len(),
             string: S::new(string),
         }
     }
 
+    /// Create a new `ImString` instance from anything that can be converted into a [`String`].
+    ///
+    /// This allows passing `&str`, [`String`], [`Cow<str>`](Cow), [`char`] or [`Box<str>`]
+    /// uniformly. The value is converted into a [`String`] and then passed to
+    /// [`from_std_string()`](ImString::from_std_string), so owned strings are not copied.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// # use std::borrow::Cow;
+    /// assert_eq!(ImString::from_into("hello"), "hello");
+    /// assert_eq!(ImString::from_into(String::from("hello")), "hello");
+    /// assert_eq!(ImString::from_into(Cow::Borrowed("hello")), "hello");
+    /// assert_eq!(ImString::from_into('h'), "h");
+    /// ```
+    pub fn from_into<T: Into<String>>(value: T) -> Self {
+        ImString::from_std_string(value.into())
+    }
+
+    /// Reads all of `reader` into a new [`ImString`].
+    ///
+    /// The input is read into a single backing string, so iterating over the
+    /// [`lines()`](ImString::lines) of the result yields zero-copy slices of it. Returns an error
+    /// if reading fails or if the input is not valid UTF-8.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// use std::io::Cursor;
+    ///
+    /// let string = ImString::read_to_imstring(Cursor::new("first\nsecond\n")).unwrap();
+    /// assert_eq!(string, "first\nsecond\n");
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn read_to_imstring<R: Read>(mut reader: R) -> std::io::Result<Self> {
+        let mut string = String::new();
+        reader.read_to_string(&mut string)?;
+        Ok(ImString::from_std_string(string))
+    }
+
     /// Truncates this string, removing all contents.
     ///
     /// If this is the only reference to the string, it will clear the backing
@@ -164,6 +290,15 @@ impl<S: Data<String>> ImString<S> {
 
     /// Returns a mutable string slice of the contents of this string.
     ///
+    /// If the backing string is not shared, this slice points into it and no copy is made. If it
+    /// is shared with other clones or slices, the contents of this view are first copied into a
+    /// new backing string, so that the changes are not visible through the other handles.
+    ///
+    /// There is deliberately no unchecked variant which skips this copy. Shared backings such as
+    /// [`Arc`](alloc::sync::Arc) only hand out shared references to their contents, and writing
+    /// through a shared reference is undefined behaviour even inside of `unsafe` code, no matter
+    /// what the caller promises about the other handles.
+    ///
     /// # Examples
     ///
     /// Basic usage:
@@ -183,7 +318,7 @@ impl<S: Data<String>> ImString<S> {
         }
 
         let string = self.string.get_mut().unwrap();
-        return &mut string[self.offset.clone()];
+        &mut string[self.offset.clone()]
     }
 
     unsafe fn try_modify_unchecked<F: FnOnce(&mut String)>(&mut self, f: F) -> bool {
@@ -210,7 +345,9 @@ impl<S: Data<String>> ImString<S> {
 
     /// Returns the length of the string in bytes.
     ///
-    /// This will not return the length in `char`s or graphemes.
+    /// This will not return the length in `char`s or graphemes. To make it explicit which length
+    /// is meant, you can use [`len_bytes()`](ImString::len_bytes), which is an alias for this
+    /// method, or [`len_chars()`](ImString::len_chars) to count the `char`s.
     ///
     /// # Example
     ///
@@ -223,6 +360,37 @@ impl<S: Data<String>> ImString<S> {
         self.offset.len()
     }
 
+    /// Returns the length of the string in bytes.
+    ///
+    /// This is an alias for [`len()`](ImString::len).
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("grüße");
+    /// assert_eq!(string.len_bytes(), 7);
+    /// ```
+    pub fn len_bytes(&self) -> usize {
+        self.len()
+    }
+
+    /// Returns the length of the string in `char`s.
+    ///
+    /// This is the same as `string.chars().count()`. It takes *O(n)* time, because every `char`
+    /// has to be decoded. It does not return the number of graphemes.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("grüße");
+    /// assert_eq!(string.len_chars(), 5);
+    /// ```
+    pub fn len_chars(&self) -> usize {
+        self.as_str().chars().count()
+    }
+
     /// Convert this string into a standard library [`String`](std::string::String).
     ///
     /// If this string has no other clones, it will return the `String` without needing to clone
@@ -234,15 +402,33 @@ impl<S: Data<String>> ImString<S> {
     /// let string = string.into_std_string();
     /// assert_eq!(string, "hello");
     /// ```
-    pub fn into_std_string(mut self) -> String {
-        if self.offset.start == 0 {
-            if let Some(string) = self.string.get_mut() {
-                string.truncate(self.offset.end);
-                return core::mem::take(string);
-            }
-        }
+    pub fn into_std_string(self) -> String {
+        let mut string = match self.string.try_unwrap() {
+            Ok(string) => string,
+            // backings which do not implement try_unwrap() can still be taken if they are unique
+            Err(mut data) => match data.get_mut() {
+                Some(string) => core::mem::take(string),
+                None => return data.get()[self.offset].to_string(),
+            },
+        };
+        string.truncate(self.offset.end);
+        string.drain(..self.offset.start);
+        string
+    }
 
-        self.as_str().to_string()
+    /// Convert this string into a standard library [`String`](std::string::String).
+    ///
+    /// This is an alias for [`into_std_string()`](ImString::into_std_string), using the name that
+    /// is common for this conversion in other crates.
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("hello");
+    /// let string = string.into_string();
+    /// assert_eq!(string, "hello");
+    /// ```
+    pub fn into_string(self) -> String {
+        self.into_std_string()
     }
 
     /// Creates a new, empty `ImString`.
@@ -311,6 +497,79 @@ impl<S: Data<String>> ImString<S> {
         ImString::from_std_string(String::from_utf16_lossy(string))
     }
 
+    /// Decode little-endian UTF-16 bytes into an [`ImString`], returning [`Err`] if they contain
+    /// any invalid data.
+    ///
+    /// Every two bytes form one UTF-16 code unit, with the least significant byte first. This is
+    /// the encoding used by Windows, for example in registry values and in UTF-16 text files. A
+    /// byte order mark is not removed. Returns [`Utf16BytesError::OddLength`] if the number of
+    /// bytes is odd.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let bytes = [0x68, 0x00, 0x69, 0x00, 0x3D, 0xD8, 0x00, 0xDE];
+    /// assert_eq!(ImString::from_utf16le_bytes(&bytes).unwrap(), "hi😀");
+    /// assert!(ImString::from_utf16le_bytes(&bytes[..3]).is_err());
+    /// ```
+    pub fn from_utf16le_bytes(bytes: &[u8]) -> Result<Self, Utf16BytesError> {
+        Self::from_utf16_bytes(bytes, u16::from_le_bytes)
+    }
+
+    /// Decode big-endian UTF-16 bytes into an [`ImString`], returning [`Err`] if they contain
+    /// any invalid data.
+    ///
+    /// Every two bytes form one UTF-16 code unit, with the most significant byte first. A byte
+    /// order mark is not removed. Returns [`Utf16BytesError::OddLength`] if the number of bytes
+    /// is odd.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let bytes = [0x00, 0x68, 0x00, 0x69, 0xD8, 0x3D, 0xDE, 0x00];
+    /// assert_eq!(ImString::from_utf16be_bytes(&bytes).unwrap(), "hi😀");
+    /// assert!(ImString::from_utf16be_bytes(&bytes[..3]).is_err());
+    /// ```
+    pub fn from_utf16be_bytes(bytes: &[u8]) -> Result<Self, Utf16BytesError> {
+        Self::from_utf16_bytes(bytes, u16::from_be_bytes)
+    }
+
+    fn from_utf16_bytes(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> Result<Self, Utf16BytesError> {
+        let pairs = bytes.chunks_exact(2);
+        if !pairs.remainder().is_empty() {
+            return Err(Utf16BytesError::OddLength);
+        }
+
+        let units = pairs.map(|pair| unit([pair[0], pair[1]]));
+        let string = char::decode_utf16(units)
+            .collect::<Result<String, _>>()
+            .map_err(Utf16BytesError::Invalid)?;
+        Ok(ImString::from_std_string(string))
+    }
+
+    /// Builds an [`ImString`] from an iterator of Unicode code points, replacing invalid ones
+    /// with the [replacement character (`U+FFFD`)](std::char::REPLACEMENT_CHARACTER).
+    ///
+    /// A code point is invalid if it lies in the surrogate range (`0xD800..=0xDFFF`) or is
+    /// larger than `0x10FFFF`. This is useful when receiving UTF-32 data, for example over FFI.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let points = [0x68, 0x69, 0xD800, 0x1F4A9, 0x110000];
+    /// assert_eq!(ImString::from_code_points_lossy(points), "hi\u{FFFD}\u{1F4A9}\u{FFFD}");
+    /// ```
+    pub fn from_code_points_lossy(iter: impl IntoIterator<Item = u32>) -> Self {
+        iter.into_iter()
+            .map(|point| char::from_u32(point).unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect()
+    }
+
     /// Converts a vector of bytes to an [`ImString`].
     ///
     /// See [`String::from_utf8()`] for more details on this function.
@@ -333,6 +592,31 @@ impl<S: Data<String>> ImString<S> {
         Ok(ImString::from_std_string(String::from_utf8(vec)?))
     }
 
+    /// Converts a slice of bytes to an [`ImString`], checking that they are valid UTF-8.
+    ///
+    /// The bytes are borrowed, so they are copied into a new string. This is useful for bytes
+    /// held in a shared buffer such as an `Arc<[u8]>`, which cannot become the backing store
+    /// since that is always a [`String`]. The bytes are validated before they are copied, so
+    /// invalid input is never copied. If you own a `Vec<u8>`, use
+    /// [`from_utf8()`](ImString::from_utf8) instead, which reuses the vector's allocation.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// use std::sync::Arc;
+    ///
+    /// let bytes: Arc<[u8]> = Arc::from(&b"hello"[..]);
+    /// let string = ImString::from_utf8_slice(&bytes).unwrap();
+    /// assert_eq!(string, "hello");
+    /// ```
+    pub fn from_utf8_slice(bytes: &[u8]) -> Result<Self, Utf8Error> {
+        let string = core::str::from_utf8(bytes)?;
+        Ok(ImString::from(string))
+    }
+
     /// Converts a slice of bytes to a string, including invalid characters.
     ///
     /// See [`String::from_utf8_lossy()`] for more details on this function.
@@ -412,10 +696,49 @@ impl<S: Data<String>> ImString<S> {
         self.into_std_string().into_bytes()
     }
 
+    /// Converts an [`ImString`] into a boxed byte slice.
+    ///
+    /// If this string has no other clones, the backing buffer is reused rather than copied, even
+    /// if this string is only a slice of it. Any excess capacity is dropped, which may cause a
+    /// reallocation.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("hello");
+    /// let bytes = string.into_boxed_bytes();
+    /// assert_eq!(&*bytes, b"hello");
+    /// ```
+    pub fn into_boxed_bytes(self) -> Box<[u8]> {
+        self.into_bytes().into_boxed_slice()
+    }
+
+    /// Converts an [`ImString`] into a boxed string slice.
+    ///
+    /// Like [`into_boxed_bytes()`](ImString::into_boxed_bytes), this reuses the backing buffer if
+    /// this string has no other clones.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("hello");
+    /// let boxed: Box<str> = string.into_boxed_str();
+    /// assert_eq!(&*boxed, "hello");
+    /// ```
+    pub fn into_boxed_str(self) -> Box<str> {
+        self.into_std_string().into_boxed_str()
+    }
+
     unsafe fn unchecked_append<F: FnOnce(String) -> String>(&mut self, f: F) {
         match self.string.get_mut() {
-            Some(mut string_ref) if self.offset.start == 0 => {
-                let mut string: String = core::mem::take(&mut string_ref);
+            Some(string_ref) if self.offset.start == 0 => {
+                let mut string: String = core::mem::take(string_ref);
                 string.truncate(self.offset.end);
                 *string_ref = f(string);
             }
@@ -425,7 +748,7 @@ impl<S: Data<String>> ImString<S> {
             }
         }
 
-        self.offset.end = self.string.get().as_bytes().len();
+        self.offset.end = self.string.get().len();
     }
 
     /// Inserts a character into this string at the specified index.
@@ -486,6 +809,25 @@ impl<S: Data<String>> ImString<S> {
         }
     }
 
+    /// Inserts a string slice at the start of this [`ImString`].
+    ///
+    /// This is equivalent to [`insert_str(0, s)`](ImString::insert_str). Prepending always
+    /// requires moving every byte of this string, so it is an *O(n)* operation. If the backing
+    /// string is shared or this string is a slice with a non-zero offset, the contents are copied
+    /// into a new allocation.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let mut string = ImString::from("world");
+    /// string.prepend("hello, ");
+    /// assert_eq!(string, "hello, world");
+    /// ```
+    pub fn prepend(&mut self, s: &str) {
+        self.insert_str(0, s);
+    }
+
     /// Shortens this [`ImString`] to the specified length.
     ///
     /// If `length` is greater than the string's current length, this has no effect.
@@ -518,6 +860,76 @@ impl<S: Data<String>> ImString<S> {
         self.offset.end = self.offset.end.min(length);
     }
 
+    /// Shortens this [`ImString`] to at most `length` bytes, rounding down to a char boundary.
+    ///
+    /// Unlike [`truncate()`](ImString::truncate), this does not panic if `length` lies inside of
+    /// a multi-byte character, but instead truncates to the largest char boundary that is not
+    /// greater than `length`. This is useful when the string has to fit into a byte budget. If
+    /// `length` is greater than the string's current length, this has no effect.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let mut string = ImString::from("añb");
+    /// string.truncate_floor(2);
+    /// assert_eq!(string, "a");
+    /// ```
+    pub fn truncate_floor(&mut self, length: usize) {
+        if length >= self.len() {
+            return;
+        }
+
+        let length = (0..=length)
+            .rev()
+            .find(|index| self.is_char_boundary(*index))
+            .unwrap_or(0);
+        self.truncate(length);
+    }
+
+    /// Shortens this [`ImString`] to `length` bytes and returns the removed tail.
+    ///
+    /// If `length` is less than the string's current length, this returns the removed bytes
+    /// `length..` as an [`ImString`] which shares the backing string with `self`. If `length` is
+    /// greater than or equal to the string's current length, this has no effect and returns
+    /// `None`. Unlike [`split_off()`](ImString::split_off), positions past the end do not panic.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `length` is less than the string's length and does not lie on a char boundary.
+    ///
+    /// # Example
+    ///
+    /// Basic usage:
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let mut string = ImString::from("hello");
+    /// assert_eq!(string.truncate_returning(2).unwrap(), "llo");
+    /// assert_eq!(string, "he");
+    /// assert_eq!(string.truncate_returning(5), None);
+    /// ```
+    pub fn truncate_returning(&mut self, length: usize) -> Option<Self> {
+        if length >= self.len() {
+            return None;
+        }
+
+        assert!(
+            self.is_char_boundary(length),
+            "truncate length {length} is not a char boundary"
+        );
+
+        let position = self.offset.start + length;
+        let tail = ImString {
+            string: self.string.clone(),
+            offset: position..self.offset.end,
+        };
+        self.offset.end = position;
+        Some(tail)
+    }
+
     /// Removes the last character from the string and returns it.
     ///
     /// If the string is empty, this returns `None`.
@@ -534,7 +946,7 @@ impl<S: Data<String>> ImString<S> {
     /// assert_eq!(string.pop(), None);
     /// ```
     pub fn pop(&mut self) -> Option<char> {
-        let last_char = self.as_str().chars().rev().next()?;
+        let last_char = self.as_str().chars().next_back()?;
         self.offset.end -= last_char.len_utf8();
         Some(last_char)
     }
@@ -587,6 +999,62 @@ impl<S: Data<String>> ImString<S> {
         }
     }
 
+    /// Appends the given string slice onto the end of this [`ImString`], without aborting if
+    /// memory cannot be allocated.
+    ///
+    /// This works like [`push_str()`](ImString::push_str), but reserves the memory it needs with
+    /// [`String::try_reserve()`]. If the backing string is shared, the copy which is made before
+    /// appending is allocated fallibly as well. If the allocation fails, or if the new length
+    /// would overflow, this returns a [`TryReserveError`] and leaves this string unchanged.
+    /// Only allocating a new backing store, such as the [`Arc`] of a copied string, can still
+    /// abort.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let mut string = ImString::from("foo");
+    /// string.push_str_checked("bar").unwrap();
+    /// assert_eq!(string, "foobar");
+    /// ```
+    pub fn push_str_checked(&mut self, s: &str) -> Result<(), TryReserveError> {
+        match self.string.get_mut() {
+            Some(string) if self.offset.start == 0 => {
+                string.truncate(self.offset.end);
+                string.try_reserve(s.len())?;
+                string.push_str(s);
+            }
+            _ => {
+                let mut string = String::new();
+                string.try_reserve_exact(self.len().saturating_add(s.len()))?;
+                string.push_str(self.as_str());
+                string.push_str(s);
+                self.string = S::new(string);
+                self.offset.start = 0;
+            }
+        }
+
+        self.offset.end = self.string.get().len();
+        Ok(())
+    }
+
+    /// Appends the given string slice onto the end of this [`ImString`].
+    ///
+    /// This is an alias for [`push_str()`](ImString::push_str), provided for symmetry with
+    /// [`prepend()`](ImString::prepend).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let mut string = ImString::from("hello");
+    /// string.append(", world");
+    /// assert_eq!(string, "hello, world");
+    /// ```
+    pub fn append(&mut self, s: &str) {
+        self.push_str(s);
+    }
+
     /// Returns `true` if this string has a length of zero, and `false` otherwise.
     ///
     /// # Examples
@@ -610,7 +1078,8 @@ impl<S: Data<String>> ImString<S> {
     /// # Panics
     ///
     /// This will panic if the specified range is invalid. In order to be valid, the lower and
-    /// upper bounds must be within this string, and must lie on a [`char`] boundary.  Use the
+    /// upper bounds must be within this string, and must lie on a [`char`] boundary.  The panic
+    /// message names the range and the length of this string. Use the
     /// [try_slice](ImString::try_slice) method if you want to handle invalid ranges.
     ///
     /// # Examples
@@ -625,8 +1094,13 @@ impl<S: Data<String>> ImString<S> {
     /// let world = string.slice(7..12);
     /// assert_eq!(world, "World");
     /// ```
+    #[track_caller]
     pub fn slice(&self, range: impl RangeBounds<usize>) -> Self {
-        self.try_slice(range).unwrap()
+        let bounds = (range.start_bound().cloned(), range.end_bound().cloned());
+        match self.try_slice(bounds) {
+            Ok(slice) => slice,
+            Err(error) => slice_failed(bounds, self.len(), error),
+        }
     }
 
     /// Try to create a new [`ImString`] containing a slice of this string.
@@ -650,19 +1124,64 @@ impl<S: Data<String>> ImString<S> {
     /// assert_eq!(world, "World");
     /// ```
     pub fn try_slice(&self, range: impl RangeBounds<usize>) -> Result<Self, SliceError> {
+        let (start, end) = self.resolve_range(&range);
+        self.check_range(start, end)?;
+        let slice = unsafe { self.slice_unchecked(range) };
+        Ok(slice)
+    }
+
+    /// Try to create a new [`ImString`] containing a slice of this string, reporting the offending
+    /// range if it fails.
+    ///
+    /// This works like [`try_slice()`](ImString::try_slice), but the error also contains the
+    /// range and the length of this string, and its [`Display`] output includes them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::{ImString, error::SliceError};
+    /// let string = ImString::from("hello");
+    /// assert_eq!(string.try_slice_detailed(1..3).unwrap(), "el");
+    ///
+    /// let error = string.try_slice_detailed(2..10).unwrap_err();
+    /// assert_eq!(error.kind(), SliceError::EndOutOfBounds);
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "end offset out of bounds: range 2..10 for string of length 5"
+    /// );
+    /// ```
+    pub fn try_slice_detailed(
+        &self,
+        range: impl RangeBounds<usize>,
+    ) -> Result<Self, SliceErrorDetailed> {
+        let (start, end) = self.resolve_range(&range);
+        self.try_slice(range)
+            .map_err(|kind| SliceErrorDetailed::new(kind, start..end, self.len()))
+    }
+
+    /// Converts the bounds of `range` into the start and end offsets used for slicing.
+    ///
+    /// The end offset is exclusive, so an inclusive end bound is converted by adding one. Bounds
+    /// which overflow saturate at `usize::MAX`, which is out of bounds for any string.
+    fn resolve_range(&self, range: &impl RangeBounds<usize>) -> (usize, usize) {
         let start = match range.start_bound() {
             Bound::Included(value) => *value,
-            Bound::Excluded(value) => *value + 1,
+            Bound::Excluded(value) => value.saturating_add(1),
             Bound::Unbounded => 0,
         };
-        if start > self.offset.len() {
-            return Err(SliceError::StartOutOfBounds);
-        }
         let end = match range.end_bound() {
-            Bound::Included(value) => *value - 1,
+            Bound::Included(value) => value.saturating_add(1),
             Bound::Excluded(value) => *value,
             Bound::Unbounded => self.offset.len(),
         };
+        (start, end)
+    }
+
+    /// Checks that `start..end` is a valid range of this string to slice with.
+    fn check_range(&self, start: usize, end: usize) -> Result<(), SliceError> {
+        if start > self.offset.len() {
+            return Err(SliceError::StartOutOfBounds);
+        }
         if end < start {
             return Err(SliceError::EndBeforeStart);
         }
@@ -675,24 +1194,49 @@ impl<S: Data<String>> ImString<S> {
         if !self.as_str().is_char_boundary(end) {
             return Err(SliceError::EndNotAligned);
         }
-        let slice = unsafe { self.slice_unchecked(range) };
-        Ok(slice)
+        Ok(())
     }
 
-    /// Create a new [`ImString`] containing a slice of this string without checking the bounds.
+    /// Checks that every span in `spans` is a valid range of this string.
     ///
-    /// # Safety
-    ///
-    /// This method is unsafe because it does not check the bounds.  If the specified range is not
-    /// invalid, for example because it points outside of this string or because the lower or upper
-    /// bound do not lie on a [`char`] boundary, this method will return an invalid [`ImString`],
-    /// which can lead to memory unsafety errors.
+    /// Each span is checked like [`try_slice()`](ImString::try_slice) checks its range, but no
+    /// slices are created. This is useful for validating spans which come from an external
+    /// source, such as a parser or a language server, before using them. Returns the index of the
+    /// first invalid span together with the reason it is invalid.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// # use imstr::ImString;
-    /// let string = ImString::from("Hello, World!");
+    /// # use imstr::{ImString, error::SliceError};
+    /// let string = ImString::from("grüße");
+    /// assert_eq!(string.validate_spans(&[0..2, 2..4, 4..7]), Ok(()));
+    /// assert_eq!(
+    ///     string.validate_spans(&[0..2, 3..4]),
+    ///     Err((1, SliceError::StartNotAligned))
+    /// );
+    /// ```
+    pub fn validate_spans(&self, spans: &[Range<usize>]) -> Result<(), (usize, SliceError)> {
+        for (index, span) in spans.iter().enumerate() {
+            self.check_range(span.start, span.end)
+                .map_err(|error| (index, error))?;
+        }
+        Ok(())
+    }
+
+    /// Create a new [`ImString`] containing a slice of this string without checking the bounds.
+    ///
+    /// # Safety
+    ///
+    /// This method is unsafe because it does not check the bounds.  If the specified range is not
+    /// invalid, for example because it points outside of this string or because the lower or upper
+    /// bound do not lie on a [`char`] boundary, this method will return an invalid [`ImString`],
+    /// which can lead to memory unsafety errors.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("Hello, World!");
     ///
     /// let hello = string.try_slice(0..5).unwrap();
     /// assert_eq!(hello, "Hello");
@@ -701,16 +1245,7 @@ impl<S: Data<String>> ImString<S> {
     /// assert_eq!(world, "World");
     /// ```
     pub unsafe fn slice_unchecked(&self, range: impl RangeBounds<usize>) -> Self {
-        let start = match range.start_bound() {
-            Bound::Included(value) => *value,
-            Bound::Excluded(value) => *value + 1,
-            Bound::Unbounded => 0,
-        };
-        let end = match range.end_bound() {
-            Bound::Included(value) => *value - 1,
-            Bound::Excluded(value) => *value,
-            Bound::Unbounded => self.offset.len(),
-        };
+        let (start, end) = self.resolve_range(&range);
         let offset = self.offset.start + start..self.offset.start + end;
         ImString {
             string: self.string.clone(),
@@ -939,6 +1474,71 @@ impl<S: Data<String>> ImString<S> {
         &self.offset
     }
 
+    /// Creates an [`ImString`] from a backing string and an offset into it.
+    ///
+    /// This is the inverse of [`raw_string()`](ImString::raw_string) and
+    /// [`raw_offset()`](ImString::raw_offset). It does not copy the backing string, so the new
+    /// [`ImString`] shares it with any other strings created from it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `offset` does not lie within the backing string, if its end is before
+    /// its start, or if either of its bounds does not lie on a [`char`] boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imstr::ImString;
+    ///
+    /// let string: ImString = ImString::from("hello world");
+    /// let world = ImString::from_raw_parts(string.raw_string(), 6..11).unwrap();
+    /// assert_eq!(world, "world");
+    /// assert!(ImString::from_raw_parts(string.raw_string(), 6..12).is_err());
+    /// ```
+    pub fn from_raw_parts(string: S, offset: Range<usize>) -> Result<Self, SliceError> {
+        let backing = string.get();
+        if offset.start > backing.len() {
+            return Err(SliceError::StartOutOfBounds);
+        }
+        if offset.end > backing.len() {
+            return Err(SliceError::EndOutOfBounds);
+        }
+        if offset.end < offset.start {
+            return Err(SliceError::EndBeforeStart);
+        }
+        if !backing.is_char_boundary(offset.start) {
+            return Err(SliceError::StartNotAligned);
+        }
+        if !backing.is_char_boundary(offset.end) {
+            return Err(SliceError::EndNotAligned);
+        }
+
+        Ok(ImString { string, offset })
+    }
+
+    /// Creates an [`ImString`] from a backing string and an offset into it, without checking
+    /// the offset.
+    ///
+    /// # Safety
+    ///
+    /// The `offset` must lie within the backing string, its end must not be before its start,
+    /// and both of its bounds must lie on a [`char`] boundary. If this is violated, the returned
+    /// [`ImString`] is invalid, which can lead to memory unsafety errors. Use
+    /// [`from_raw_parts()`](ImString::from_raw_parts) to check these conditions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imstr::ImString;
+    ///
+    /// let string: ImString = ImString::from("hello world");
+    /// let hello = unsafe { ImString::from_raw_parts_unchecked(string.raw_string(), 0..5) };
+    /// assert_eq!(hello, "hello");
+    /// ```
+    pub unsafe fn from_raw_parts_unchecked(string: S, offset: Range<usize>) -> Self {
+        ImString { string, offset }
+    }
+
     /// Sets the `ImString`'s `offset` to the given `Range<usize>`.
     ///
     /// The `offset` represents the start and end positions of the `ImString`'s view
@@ -948,7 +1548,11 @@ impl<S: Data<String>> ImString<S> {
     ///
     /// # Returns
     ///
-    /// Returns an error if the given `offset` is not a valid range within the underlying `String`.
+    /// Returns an error if the given `offset` is not a valid range within the underlying `String`:
+    /// [`SliceError::StartOutOfBounds`] or [`SliceError::EndOutOfBounds`] if it points past the
+    /// end of the underlying `String`, [`SliceError::EndBeforeStart`] if it is reversed, and
+    /// [`SliceError::StartNotAligned`] or [`SliceError::EndNotAligned`] if it does not lie on
+    /// [`char`] boundaries.
     ///
     /// # Examples
     ///
@@ -963,14 +1567,20 @@ impl<S: Data<String>> ImString<S> {
     pub fn try_set_offset(&mut self, range: impl RangeBounds<usize>) -> Result<(), SliceError> {
         let start = match range.start_bound() {
             Bound::Included(value) => *value,
-            Bound::Excluded(value) => *value + 1,
+            Bound::Excluded(value) => value.saturating_add(1),
             Bound::Unbounded => 0,
         };
         let end = match range.end_bound() {
-            Bound::Included(value) => *value - 1,
+            Bound::Included(value) => value.saturating_add(1),
             Bound::Excluded(value) => *value,
             Bound::Unbounded => self.offset.len(),
         };
+        if start > self.string.get().len() {
+            return Err(SliceError::StartOutOfBounds);
+        }
+        if end > self.string.get().len() {
+            return Err(SliceError::EndOutOfBounds);
+        }
         if end < start {
             return Err(SliceError::EndBeforeStart);
         }
@@ -985,6 +1595,34 @@ impl<S: Data<String>> ImString<S> {
         Ok(())
     }
 
+    /// Returns a new view of the backing string at the given `offset`.
+    ///
+    /// This is the non-mutating version of [`try_set_offset()`](ImString::try_set_offset): it
+    /// leaves this string unchanged and returns a new [`ImString`] which shares the backing string
+    /// with it. Like [`try_set_offset()`](ImString::try_set_offset), and unlike
+    /// [`try_slice()`](ImString::try_slice), the `offset` is relative to the start of the backing
+    /// string and not to this view, so it may select bytes outside of this view.
+    ///
+    /// Returns the same errors as [`try_set_offset()`](ImString::try_set_offset) if `offset` is
+    /// not a valid range of the backing string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use imstr::ImString;
+    ///
+    /// let string: ImString = ImString::from("hello world");
+    /// let world = string.slice(6..);
+    /// assert_eq!(world.with_offset(0..5).unwrap(), "hello");
+    /// assert_eq!(world, "world");
+    /// assert!(world.with_offset(6..12).is_err());
+    /// ```
+    pub fn with_offset(&self, offset: Range<usize>) -> Result<Self, SliceError> {
+        let mut string = self.clone();
+        string.try_set_offset(offset)?;
+        Ok(string)
+    }
+
     /// An iterator over the lines of a string.
     ///
     /// Lines are split at line endings that are either newlines (`\n`) or sequences of a carriage
@@ -1016,6 +1654,32 @@ impl<S: Data<String>> ImString<S> {
         }
     }
 
+    /// Returns an iterator over the `char`s of this string as single-char [`ImString`]s, with
+    /// their byte index.
+    ///
+    /// This is like [`char_indices()`](ImString::char_indices), but every `char` is returned as
+    /// a slice which shares the backing string with this one. This is useful when downstream code
+    /// expects [`ImString`]s everywhere.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("añb");
+    /// let slices: Vec<(usize, ImString)> = string.char_slices().collect();
+    /// assert_eq!(slices[1].0, 1);
+    /// assert_eq!(slices[1].1, "ñ");
+    /// assert_eq!(slices[2].0, 3);
+    /// ```
+    pub fn char_slices(&self) -> impl Iterator<Item = (usize, Self)> + '_ {
+        self.as_str().char_indices().map(|(index, c)| {
+            // SAFETY: char_indices() yields the byte offset of each char, which is a char
+            // boundary, and adding the length of the char gives the next char boundary.
+            let slice = unsafe { self.slice_unchecked(index..index + c.len_utf8()) };
+            (index, slice)
+        })
+    }
+
     /// Returns a slice of this string with leading and trailing whitespace removed.
     ///
     /// *Whitespace* is defined according to the terms of the Unicode Derived Core Property
@@ -1069,1037 +1733,4826 @@ impl<S: Data<String>> ImString<S> {
     pub fn trim_end(&self) -> Self {
         self.str_ref(self.as_str().trim_end())
     }
-}
 
-impl<S: Data<String>> Default for ImString<S> {
-    fn default() -> Self {
-        ImString::new()
-    }
-}
+    /// Returns a slice of this string with leading and trailing whitespace removed, along with
+    /// the number of bytes removed from the start and the end.
+    ///
+    /// This works like [`trim()`](ImString::trim), but makes it possible to map positions in the
+    /// trimmed string back to positions in this string.
+    ///
+    /// # Examples
+    ///
+    /// Basic usage:
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("\n Hello\tWorld\t\n");
+    /// let (trimmed, start, end) = string.trim_with_offsets();
+    /// assert_eq!(trimmed, "Hello\tWorld");
+    /// assert_eq!((start, end), (2, 2));
+    /// assert_eq!(trimmed, &string[start..string.len() - end]);
+    /// ```
+    pub fn trim_with_offsets(&self) -> (Self, usize, usize) {
+        let start = self.len() - self.as_str().trim_start().len();
+        let end = self.len() - self.as_str().trim_end().len();
+        if start == self.len() {
+            return (self.slice(start..), start, 0);
+        }
 
-impl<S: Data<String>> From<&str> for ImString<S> {
-    fn from(string: &str) -> Self {
-        ImString::from_std_string(string.to_string())
+        // SAFETY: both offsets come from trimming the string, so they lie on char boundaries.
+        let trimmed = unsafe { self.slice_unchecked(start..self.len() - end) };
+        (trimmed, start, end)
     }
-}
 
-impl<S: Data<String>> From<char> for ImString<S> {
-    fn from(c: char) -> Self {
-        String::from(c).into()
+    /// Parses this string into another type, ignoring leading and trailing whitespace.
+    ///
+    /// This works like [`str::parse`], except that it [trims](ImString::trim) the string first
+    /// and discards the error. It is useful for parsing tokens that may be surrounded by
+    /// whitespace.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from(" 14 ");
+    /// assert_eq!(string.parse_trimmed::<u32>(), Some(14));
+    /// assert_eq!(string.parse::<u32>().ok(), None);
+    /// ```
+    pub fn parse_trimmed<T: FromStr>(&self) -> Option<T> {
+        self.as_str().trim().parse().ok()
     }
-}
 
-impl<S: Data<String>> From<String> for ImString<S> {
-    fn from(string: String) -> Self {
-        ImString::from_std_string(string)
-    }
-}
+    /// Returns a copy of this string with every non-ASCII character replaced by `replacement`.
+    ///
+    /// This is useful when writing to sinks that only support ASCII, such as some log
+    /// formats. If the string is already all ASCII, this returns a clone which shares the backing
+    /// string, without copying.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `replacement` is not an ASCII character.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("Grüße, Jürgen");
+    /// assert_eq!(string.to_ascii_safe('?'), "Gr??e, J?rgen");
+    /// ```
+    pub fn to_ascii_safe(&self, replacement: char) -> Self {
+        assert!(
+            replacement.is_ascii(),
+            "replacement character {replacement:?} is not ASCII"
+        );
+
+        if self.as_str().is_ascii() {
+            return self.clone();
+        }
 
-impl<'a, S: Data<String>> From<Cow<'a, str>> for ImString<S> {
-    fn from(string: Cow<'a, str>) -> Self {
-        ImString::from(string.into_owned())
+        self.as_str()
+            .chars()
+            .map(|c| if c.is_ascii() { c } else { replacement })
+            .collect()
     }
-}
 
-impl<S: Data<String>> From<ImString<S>> for String {
-    fn from(string: ImString<S>) -> Self {
-        string.into_std_string()
+    /// Returns an iterator over every byte offset in this string which lies on a [`char`]
+    /// boundary.
+    ///
+    /// These are exactly the positions this string can be sliced or split at. The offsets are
+    /// relative to this string, and always include `0` and [`len()`](ImString::len).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("aüb");
+    /// let boundaries: Vec<usize> = string.char_boundaries().collect();
+    /// assert_eq!(boundaries, [0, 1, 3, 4]);
+    /// ```
+    pub fn char_boundaries(&self) -> impl Iterator<Item = usize> + '_ {
+        self.as_str()
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain(core::iter::once(self.len()))
     }
-}
 
-impl<S: Data<String>> PartialEq<str> for ImString<S> {
-    fn eq(&self, other: &str) -> bool {
-        self.as_str().eq(other)
+    /// Wraps this string into lines of at most `width` characters.
+    ///
+    /// Lines are broken at whitespace where possible, and the whitespace at the break is dropped,
+    /// as is leading whitespace. Words that are longer than `width` are broken at a [`char`]
+    /// boundary instead. Existing line breaks are preserved. The width is measured in [`char`]s,
+    /// not in bytes or graphemes.
+    ///
+    /// Since every line is a slice of this string, they all share the backing string without
+    /// copying.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("the quick brown fox");
+    /// assert_eq!(string.wrap(10), ["the quick", "brown fox"]);
+    /// ```
+    pub fn wrap(&self, width: usize) -> Vec<Self> {
+        assert!(width > 0, "wrap width must be non-zero");
+        let mut lines = Vec::new();
+        for line in self.as_str().lines() {
+            let mut rest = line.trim_start();
+            loop {
+                // byte offset at which rest exceeds the width
+                let limit = match rest.char_indices().nth(width) {
+                    Some((limit, _)) => limit,
+                    None => {
+                        lines.push(self.str_ref(rest));
+                        break;
+                    }
+                };
+
+                // prefer breaking at whitespace, including right after the last char that fits
+                let next = rest[limit..].chars().next().map_or(0, char::len_utf8);
+                let head = match rest[..limit + next].rfind(char::is_whitespace) {
+                    Some(position) if position > 0 => &rest[..position],
+                    _ => &rest[..limit],
+                };
+
+                let trimmed = head.trim_end();
+                if !trimmed.is_empty() {
+                    lines.push(self.str_ref(trimmed));
+                }
+                rest = rest[head.len()..].trim_start();
+                if rest.is_empty() {
+                    break;
+                }
+            }
+        }
+        lines
     }
-}
 
-impl<'a, S: Data<String>> PartialEq<&'a str> for ImString<S> {
-    fn eq(&self, other: &&'a str) -> bool {
-        self.as_str().eq(*other)
+    /// An iterator over the lines of a string, along with their byte ranges.
+    ///
+    /// This works like [`lines()`](ImString::lines), except that every line is yielded together
+    /// with its byte range, relative to this string. The ranges do not include the line
+    /// terminators. This is useful for building a line index without having to compute the
+    /// offsets of the lines afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("first\r\nsecond\n");
+    /// let lines: Vec<_> = string.line_ranges().collect();
+    /// assert_eq!(lines[0], (ImString::from("first"), 0..5));
+    /// assert_eq!(lines[1], (ImString::from("second"), 7..13));
+    /// ```
+    pub fn line_ranges(&self) -> impl Iterator<Item = (Self, Range<usize>)> + '_ {
+        self.as_str().lines().map(move |line| {
+            let range = try_slice_offset(self.as_bytes(), line.as_bytes()).unwrap();
+            (unsafe { self.slice_unchecked(range.clone()) }, range)
+        })
     }
-}
 
-impl<S: Data<String>> PartialEq<String> for ImString<S> {
-    fn eq(&self, other: &String) -> bool {
-        self.as_str().eq(other.as_str())
+    /// An iterator over the lines of a string, along with the line ending which terminated them.
+    ///
+    /// Like [`lines()`](ImString::lines), this splits on `\n` and `\r\n`, and the yielded lines
+    /// do not include their terminators. They are slices of this string and share its backing
+    /// string. The [`LineEnding`] records how each line was terminated, so that the string can be
+    /// reassembled faithfully, or the line endings normalized deliberately. Only the final line
+    /// can have no terminator, and a trailing line ending does not produce an empty final line.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// # use imstr::string::LineEnding;
+    /// let string = ImString::from("first\r\nsecond\nthird");
+    /// let lines: Vec<_> = string.lines_with_endings().collect();
+    /// assert_eq!(lines[0], (ImString::from("first"), LineEnding::CrLf));
+    /// assert_eq!(lines[1], (ImString::from("second"), LineEnding::Lf));
+    /// assert_eq!(lines[2], (ImString::from("third"), LineEnding::None));
+    /// ```
+    pub fn lines_with_endings(&self) -> impl Iterator<Item = (Self, LineEnding)> + '_ {
+        let mut start = 0;
+        self.as_str().split_inclusive('\n').map(move |line| {
+            let (content, ending) = if let Some(content) = line.strip_suffix("\r\n") {
+                (content, LineEnding::CrLf)
+            } else if let Some(content) = line.strip_suffix('\n') {
+                (content, LineEnding::Lf)
+            } else {
+                (line, LineEnding::None)
+            };
+            // SAFETY: the line starts after a `\n` or at the start of the string, and the content
+            // ends before an ASCII line terminator, so both offsets are char boundaries.
+            let content = unsafe { self.slice_unchecked(start..start + content.len()) };
+            start += line.len();
+            (content, ending)
+        })
     }
-}
 
-impl<S: Data<String>, O: Data<String>> PartialEq<ImString<O>> for ImString<S> {
-    fn eq(&self, other: &ImString<O>) -> bool {
-        self.as_str().eq(other.as_str())
+    /// Returns the longest prefix this string has in common with `other`.
+    ///
+    /// The prefix is compared [`char`] by [`char`], so it always ends on a char boundary, even
+    /// if the strings share some bytes of a multibyte sequence. The returned string is a slice of
+    /// this string, and shares its backing string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("interstellar");
+    /// assert_eq!(string.common_prefix(&ImString::from("internet")), "inter");
+    /// assert_eq!(string.common_prefix(&ImString::from("galaxy")), "");
+    /// ```
+    pub fn common_prefix(&self, other: &Self) -> Self {
+        let length: usize = self
+            .as_str()
+            .chars()
+            .zip(other.as_str().chars())
+            .take_while(|(a, b)| a == b)
+            .map(|(c, _)| c.len_utf8())
+            .sum();
+        unsafe { self.slice_unchecked(..length) }
     }
-}
 
-impl<S: Data<String>> Eq for ImString<S> {}
-
-impl<S: Data<String>> PartialOrd<ImString<S>> for ImString<S> {
-    fn partial_cmp(&self, other: &ImString<S>) -> Option<Ordering> {
-        self.as_str().partial_cmp(other.as_str())
+    /// Returns the longest suffix this string has in common with `other`.
+    ///
+    /// The suffix is compared [`char`] by [`char`], so it always starts on a char boundary, even
+    /// if the strings share some bytes of a multibyte sequence. The returned string is a slice of
+    /// this string, and shares its backing string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("running");
+    /// assert_eq!(string.common_suffix(&ImString::from("jumping")), "ing");
+    /// assert_eq!(string.common_suffix(&ImString::from("runner")), "");
+    /// ```
+    pub fn common_suffix(&self, other: &Self) -> Self {
+        let length: usize = self
+            .as_str()
+            .chars()
+            .rev()
+            .zip(other.as_str().chars().rev())
+            .take_while(|(a, b)| a == b)
+            .map(|(c, _)| c.len_utf8())
+            .sum();
+        unsafe { self.slice_unchecked(self.len() - length..) }
     }
-}
 
-impl<S: Data<String>> Ord for ImString<S> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.as_str().cmp(other.as_str())
-    }
-}
+    /// Returns a copy of this string with every occurrence of `from` replaced by `to`.
+    ///
+    /// If `from` does not occur in the string, this returns a clone which shares the backing
+    /// string, without copying. If both characters have the same UTF-8 encoded length, which is
+    /// always the case when replacing ASCII with ASCII, the replacement is done on a single copy of
+    /// the string without moving any of the other bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("a-b-c");
+    /// assert_eq!(string.replace_char('-', '+'), "a+b+c");
+    /// assert_eq!(string.replace_char('-', '€'), "a€b€c");
+    /// ```
+    pub fn replace_char(&self, from: char, to: char) -> Self {
+        let mut matches = self.as_str().match_indices(from).map(|(index, _)| index);
+        let first = match matches.next() {
+            Some(index) => index,
+            None => return self.clone(),
+        };
 
-impl<S: Data<String>> Debug for ImString<S> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
-        Debug::fmt(self.as_str(), f)
+        let mut buffer = [0; 4];
+        let to = to.encode_utf8(&mut buffer);
+        if from.len_utf8() != to.len() {
+            return Self::from(self.as_str().replace(from, to));
+        }
+
+        let mut string = String::from(self.as_str());
+        for index in core::iter::once(first).chain(matches) {
+            string.replace_range(index..index + to.len(), to);
+        }
+        Self::from(string)
     }
-}
 
-impl<S: Data<String>> Display for ImString<S> {
-    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
-        Display::fmt(self.as_str(), formatter)
+    /// Applies `f` to the contents of this string and returns the result as a new [`ImString`].
+    ///
+    /// This is a shorthand for `ImString::from(f(string.as_str()))`, which keeps the backing type
+    /// of this string and reads well in chains of transformations. The result is a new string, it
+    /// does not share the backing string with this one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("hello");
+    /// assert_eq!(string.map_str(str::to_uppercase), "HELLO");
+    /// ```
+    pub fn map_str<F: FnOnce(&str) -> String>(&self, f: F) -> Self {
+        Self::from(f(self.as_str()))
     }
-}
 
-impl<S: Data<String>> FromStr for ImString<S> {
-    type Err = Infallible;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(ImString::from(s))
+    /// Returns a new string built from the characters for which `f` returns `Some`.
+    ///
+    /// Every character of this string is passed to `f`. If it returns `Some(c)`, `c` is added to
+    /// the result, and if it returns `None`, the character is dropped. This removes and replaces
+    /// characters in a single pass. This always allocates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("pin 1234");
+    /// let masked = string.filter_map_chars(|c| match c {
+    ///     ' ' => None,
+    ///     c if c.is_ascii_digit() => Some('#'),
+    ///     c => Some(c),
+    /// });
+    /// assert_eq!(masked, "pin####");
+    /// ```
+    pub fn filter_map_chars<F: FnMut(char) -> Option<char>>(&self, f: F) -> Self {
+        self.as_str().chars().filter_map(f).collect()
     }
-}
 
-// Delegate hash to contained str. This is important!
-impl<S: Data<String>> Hash for ImString<S> {
-    fn hash<H: Hasher>(&self, hasher: &mut H) {
-        self.as_str().hash(hasher)
+    /// Returns a copy of this string with every character escaped using
+    /// [`char::escape_default()`].
+    ///
+    /// This is the same as [`str::escape_default()`], but collects the result into a new
+    /// string. This always allocates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("line\n\"quoted\"");
+    /// assert_eq!(string.escape_default(), "line\\n\\\"quoted\\\"");
+    /// ```
+    pub fn escape_default(&self) -> Self {
+        self.as_str().escape_default().collect()
     }
-}
 
-impl<S: Data<String>> Write for ImString<S> {
-    fn write_str(&mut self, string: &str) -> Result<(), FmtError> {
-        self.push_str(string);
-        Ok(())
+    /// Returns a copy of this string with every character escaped using
+    /// [`char::escape_debug()`].
+    ///
+    /// This is the same as [`str::escape_debug()`], but collects the result into a new string.
+    /// Unlike [`escape_default()`](ImString::escape_default), printable Unicode characters are
+    /// left as they are. This always allocates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("tab\there: ü");
+    /// assert_eq!(string.escape_debug(), "tab\\there: ü");
+    /// ```
+    pub fn escape_debug(&self) -> Self {
+        self.as_str().escape_debug().collect()
     }
 
-    fn write_char(&mut self, c: char) -> Result<(), FmtError> {
-        self.push(c);
-        Ok(())
-    }
-}
+    /// Returns a copy of this string with escape sequences replaced by the characters they stand
+    /// for.
+    ///
+    /// This is the inverse of [`escape_default()`](ImString::escape_default) and
+    /// [`escape_debug()`](ImString::escape_debug). It understands `\n`, `\r`, `\t`, `\0`,
+    /// `\\`, `\'` and `\"`, as well as ASCII hex escapes like `\x41` and Unicode escapes like
+    /// `\u{1f600}`. If the string does not contain a backslash, this returns a clone which shares
+    /// the backing string, without copying.
+    ///
+    /// Returns an [`EscapeError`] if the string contains a malformed or unknown escape sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("tab\\there: \\u{fc}\\x21");
+    /// assert_eq!(string.decode_escapes().unwrap(), "tab\there: ü!");
+    /// assert!(ImString::from("\\q").decode_escapes().is_err());
+    /// ```
+    pub fn decode_escapes(&self) -> Result<Self, EscapeError> {
+        if !self.as_str().contains('\\') {
+            return Ok(self.clone());
+        }
 
-impl<S: Data<String>> Index<Range<usize>> for ImString<S> {
-    type Output = str;
-    fn index(&self, index: Range<usize>) -> &str {
-        &self.as_str()[index]
+        let mut string = String::with_capacity(self.len());
+        let mut chars = self.as_str().chars();
+        while let Some(c) = chars.next() {
+            if c == '\\' {
+                string.push(decode_escape(&mut chars)?);
+            } else {
+                string.push(c);
+            }
+        }
+        Ok(Self::from(string))
     }
-}
 
-impl<S: Data<String>> Index<RangeFrom<usize>> for ImString<S> {
-    type Output = str;
-    fn index(&self, index: RangeFrom<usize>) -> &str {
-        &self.as_str()[index]
+    /// Returns the contents of this string as a borrowed [`Cow<str>`](Cow).
+    ///
+    /// This always returns [`Cow::Borrowed`] and never allocates. It is useful for passing an
+    /// [`ImString`] to code which expects a [`Cow<str>`](Cow).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// # use std::borrow::Cow;
+    /// let string = ImString::from("hello");
+    /// assert!(matches!(string.as_cow(), Cow::Borrowed("hello")));
+    /// ```
+    pub fn as_cow(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.as_str())
     }
-}
 
-impl<S: Data<String>> Index<RangeFull> for ImString<S> {
-    type Output = str;
-    fn index(&self, index: RangeFull) -> &str {
-        &self.as_str()[index]
+    /// Converts this string into an owned [`Cow<str>`](Cow).
+    ///
+    /// This always returns [`Cow::Owned`]. Like [`into_std_string()`](ImString::into_std_string),
+    /// it does not need to copy the string if it has no other clones.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// # use std::borrow::Cow;
+    /// let string = ImString::from("hello");
+    /// let cow: Cow<'static, str> = string.into_cow();
+    /// assert_eq!(cow, "hello");
+    /// ```
+    pub fn into_cow(self) -> Cow<'static, str> {
+        Cow::Owned(self.into_std_string())
     }
-}
 
-impl<S: Data<String>> Index<RangeInclusive<usize>> for ImString<S> {
-    type Output = str;
-    fn index(&self, index: RangeInclusive<usize>) -> &str {
-        &self.as_str()[index]
+    /// Splits this string on `separator` and parses each part.
+    ///
+    /// This is a shorthand for calling [`split()`](str::split) and then parsing every part with
+    /// [`str::parse()`]. The parts are parsed lazily, so collecting into a
+    /// [`Result`] stops at the first part which fails to parse.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("1,2,3");
+    /// let numbers: Result<Vec<u32>, _> = string.split_parse(",").collect();
+    /// assert_eq!(numbers, Ok(vec![1, 2, 3]));
+    ///
+    /// let string = ImString::from("1,x,3");
+    /// let numbers: Result<Vec<u32>, _> = string.split_parse(",").collect();
+    /// assert!(numbers.is_err());
+    /// ```
+    pub fn split_parse<'a, T: FromStr + 'a>(
+        &'a self,
+        separator: &'a str,
+    ) -> impl Iterator<Item = Result<T, T::Err>> + 'a {
+        self.as_str().split(separator).map(str::parse)
     }
-}
 
-impl<S: Data<String>> Index<RangeTo<usize>> for ImString<S> {
-    type Output = str;
-    fn index(&self, index: RangeTo<usize>) -> &str {
-        &self.as_str()[index]
+    /// Searches the lines of this string for `target`, assuming that they are sorted.
+    ///
+    /// This performs a binary search over the bytes of the string, so it takes *O(log n)* line
+    /// comparisons and does not need to split the string into lines first. Lines are split like
+    /// [`lines()`](ImString::lines). If the lines are not sorted, the result is unspecified. If
+    /// `target` occurs more than once, any one of the matches may be returned.
+    ///
+    /// Returns the matching line, which shares the backing string with this one, or `None` if
+    /// `target` is not one of the lines.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let words = ImString::from("apple\nbanana\ncherry\n");
+    /// assert_eq!(words.search_line("banana").unwrap(), "banana");
+    /// assert_eq!(words.search_line("blueberry"), None);
+    /// ```
+    pub fn search_line(&self, target: &str) -> Option<Self> {
+        let bytes = self.as_bytes();
+        let mut low = 0;
+        let mut high = bytes.len();
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let start = bytes[..middle]
+                .iter()
+                .rposition(|byte| *byte == b'\n')
+                .map(|index| index + 1)
+                .unwrap_or(0);
+            let end = bytes[middle..]
+                .iter()
+                .position(|byte| *byte == b'\n')
+                .map(|index| middle + index)
+                .unwrap_or(bytes.len());
+            let line = &self.as_str()[start..end];
+            // like lines(), only strip a `\r` which is part of a `\r\n` terminator
+            let line = match line.strip_suffix('\r') {
+                Some(stripped) if end < bytes.len() => stripped,
+                _ => line,
+            };
+            match line.cmp(target) {
+                Ordering::Equal => return Some(self.str_ref(line)),
+                Ordering::Less => low = end + 1,
+                Ordering::Greater => high = start,
+            }
+        }
+        None
     }
-}
 
-impl<S: Data<String>> IndexMut<Range<usize>> for ImString<S> {
-    fn index_mut(&mut self, index: Range<usize>) -> &mut str {
-        &mut self.as_mut_str()[index]
-    }
-}
+    /// Returns a slice of `char_len` characters, starting at the character index `char_start`.
+    ///
+    /// Unlike [`slice()`](ImString::slice), the indices count [`char`]s rather than bytes. The
+    /// returned string shares the backing string with this one. Returns `None` if the requested
+    /// range extends past the end of the string, rather than clamping it.
+    ///
+    /// This takes *O(n)* time, because the character indices have to be converted into byte
+    /// offsets.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("Grüße, Jürgen");
+    /// assert_eq!(string.substring(2, 3).unwrap(), "üße");
+    /// assert_eq!(string.substring(7, 6).unwrap(), "Jürgen");
+    /// assert_eq!(string.substring(7, 7), None);
+    /// ```
+    pub fn substring(&self, char_start: usize, char_len: usize) -> Option<Self> {
+        let mut offsets = self
+            .as_str()
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain(core::iter::once(self.len()));
+        let start = offsets.nth(char_start)?;
+        let end = match char_len {
+            0 => start,
+            char_len => offsets.nth(char_len - 1)?,
+        };
 
-impl<S: Data<String>> IndexMut<RangeFrom<usize>> for ImString<S> {
-    fn index_mut(&mut self, index: RangeFrom<usize>) -> &mut str {
-        &mut self.as_mut_str()[index]
+        // SAFETY: both offsets come from char_indices(), so they lie on char boundaries.
+        Some(unsafe { self.slice_unchecked(start..end) })
     }
-}
 
-impl<S: Data<String>> IndexMut<RangeFull> for ImString<S> {
-    fn index_mut(&mut self, index: RangeFull) -> &mut str {
-        &mut self.as_mut_str()[index]
+    /// Compares this string with `other`, skipping every character which is in `ignore`.
+    ///
+    /// This is useful for comparing identifiers which only differ in separators, for example
+    /// `kebab-case` and `snake_case` names.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("foo_bar-baz");
+    /// assert!(string.eq_ignoring("foo-bar_baz", &['_', '-']));
+    /// assert!(string.eq_ignoring("foobarbaz", &['_', '-']));
+    /// assert!(!string.eq_ignoring("foobarbaz", &['_']));
+    /// ```
+    pub fn eq_ignoring(&self, other: &str, ignore: &[char]) -> bool {
+        let filter = |c: &char| !ignore.contains(c);
+        self.as_str()
+            .chars()
+            .filter(filter)
+            .eq(other.chars().filter(filter))
     }
-}
 
-impl<S: Data<String>> IndexMut<RangeInclusive<usize>> for ImString<S> {
-    fn index_mut(&mut self, index: RangeInclusive<usize>) -> &mut str {
-        &mut self.as_mut_str()[index]
+    /// Returns the index of the first of `options` which is equal to this string.
+    ///
+    /// Returns `None` if none of them is equal. This is useful for parsing a token into one of
+    /// a fixed set of values, such as the variants of an enum, without a chain of comparisons.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let options = ["debug", "info", "warn", "error"];
+    /// assert_eq!(ImString::from("info").eq_any(&options), Some(1));
+    /// assert_eq!(ImString::from("trace").eq_any(&options), None);
+    /// ```
+    pub fn eq_any(&self, options: &[&str]) -> Option<usize> {
+        options.iter().position(|option| self.as_str() == *option)
     }
-}
 
-impl<S: Data<String>> IndexMut<RangeTo<usize>> for ImString<S> {
-    fn index_mut(&mut self, index: RangeTo<usize>) -> &mut str {
-        &mut self.as_mut_str()[index]
-    }
-}
+    /// Splits this string after the leading run of characters for which `predicate` is `true`.
+    ///
+    /// Returns the leading run and the remainder, both of which share the backing string with
+    /// this one. If no character matches, the run is empty. If every character matches, the
+    /// remainder is empty. This is useful for writing simple lexers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("abc123");
+    /// let (word, rest) = string.take_while_char(char::is_alphabetic);
+    /// assert_eq!(word, "abc");
+    /// assert_eq!(rest, "123");
+    /// ```
+    pub fn take_while_char<F: Fn(char) -> bool>(&self, predicate: F) -> (Self, Self) {
+        let position = self.as_str().find(|c| !predicate(c)).unwrap_or(self.len());
 
-/// Iterator over lines of an [`ImString`].
-///
-/// Unlike the [`Lines`](std::str::Lines) iterator of [`str`], this iterator returns instances of
-/// [`ImString`].
-///
-/// # Example
-///
-/// ```rust
-/// # use imstr::ImString;
-/// let string = ImString::from("multi\nline\ninput");
-/// let lines: Vec<ImString> = string.lines().collect();
-/// assert_eq!(lines[0], "multi");
-/// assert_eq!(lines[1], "line");
-/// assert_eq!(lines[2], "input");
-/// ```
-pub type Lines<'a, S> = ImStringIterator<'a, S, core::str::Lines<'a>>;
+        // SAFETY: find() returns the byte offset of a char, which is always a char boundary.
+        unsafe {
+            (
+                self.slice_unchecked(..position),
+                self.slice_unchecked(position..),
+            )
+        }
+    }
 
-/// Iterator wrapper over string slices of an [`ImString`].
-///
-/// This iterator wrapper turns string slices of an [`ImString`] into [`ImString`]s.
-pub struct ImStringIterator<'a, S: Data<String>, I: Iterator<Item = &'a str>> {
-    string: S,
-    iterator: I,
-}
+    /// Returns a copy of this string with all line endings converted to `\n`.
+    ///
+    /// Both Windows (`\r\n`) and classic Mac OS (`\r`) line endings are converted. If the string
+    /// does not contain any `\r`, this returns a clone which shares the backing string, without
+    /// copying.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("first\r\nsecond\rthird\n");
+    /// assert_eq!(string.normalize_newlines(), "first\nsecond\nthird\n");
+    /// ```
+    pub fn normalize_newlines(&self) -> Self {
+        if !self.as_str().contains('\r') {
+            return self.clone();
+        }
 
-impl<'a, S: Data<String>, I: Iterator<Item = &'a str>> Iterator for ImStringIterator<'a, S, I> {
-    type Item = ImString<S>;
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.iterator.next() {
-            Some(slice) => {
-                let offset =
-                    try_slice_offset(self.string.get().as_bytes(), slice.as_bytes()).unwrap();
-                Some(ImString {
-                    string: self.string.clone(),
-                    offset,
-                })
+        let mut string = String::with_capacity(self.len());
+        let mut chars = self.as_str().chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '\r' {
+                chars.next_if_eq(&'\n');
+                string.push('\n');
+            } else {
+                string.push(c);
             }
-            None => None,
         }
+        Self::from(string)
     }
-}
 
-impl<'a, S: Data<String>, I: Iterator<Item = &'a str>> ImStringIterator<'a, S, I> {
-    fn new(string: S, iterator: I) -> Self {
-        ImStringIterator { string, iterator }
+    /// Returns `true` if this string ends with a `\n`.
+    ///
+    /// This also holds for strings ending in a Windows (`\r\n`) line ending.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// assert!(ImString::from("line\n").ends_with_newline());
+    /// assert!(!ImString::from("line").ends_with_newline());
+    /// ```
+    pub fn ends_with_newline(&self) -> bool {
+        self.ends_with('\n')
     }
-}
-
-/// Iterator over `char`s with their corresponding byte index inside an `ImString`.
-#[derive(Clone, Debug)]
-pub struct CharIndices<S: Data<String>> {
-    offset: usize,
-    string: ImString<S>,
-}
 
-impl<S: Data<String>> Iterator for CharIndices<S> {
-    type Item = (usize, char);
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.string.as_str().chars().next() {
-            Some(c) => {
-                let len = c.len_utf8();
-                self.string = self.string.slice(len..);
-                let offset = self.offset;
-                self.offset += len;
-                Some((offset, c))
-            }
-            None => None,
+    /// Returns this string with a `\n` appended, unless it already ends with one.
+    ///
+    /// If the string already ends with a newline, or if it is empty, this returns a clone which
+    /// shares the backing string, without copying. An empty string is left as it is, because it
+    /// does not contain an unterminated line.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// assert_eq!(ImString::from("line").ensure_trailing_newline(), "line\n");
+    /// assert_eq!(ImString::from("line\n").ensure_trailing_newline(), "line\n");
+    /// assert_eq!(ImString::from("").ensure_trailing_newline(), "");
+    /// ```
+    pub fn ensure_trailing_newline(&self) -> Self {
+        if self.is_empty() || self.ends_with_newline() {
+            return self.clone();
         }
-    }
-}
 
-/// Iterator over `char`s inside an `ImString`.
-#[derive(Clone, Debug)]
-pub struct Chars<S: Data<String>> {
-    string: ImString<S>,
-}
+        let mut string = self.clone();
+        string.push('\n');
+        string
+    }
 
-impl<S: Data<String>> Iterator for Chars<S> {
-    type Item = char;
-    fn next(&mut self) -> Option<Self::Item> {
-        match self.string.as_str().chars().next() {
-            Some(c) => {
-                self.string = self.string.slice(c.len_utf8()..);
-                Some(c)
-            }
-            None => None,
+    /// Returns a slice of this string with a leading UTF-8 byte order mark (`U+FEFF`) removed.
+    ///
+    /// Text files produced on Windows often start with a byte order mark, which usually needs to
+    /// be removed before parsing. If the string does not start with one, this returns a clone.
+    /// In both cases, the returned string shares the backing string with this one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("\u{feff}name,value");
+    /// assert_eq!(string.strip_bom(), "name,value");
+    /// assert_eq!(string.strip_bom().strip_bom(), "name,value");
+    /// ```
+    pub fn strip_bom(&self) -> Self {
+        match self.as_str().strip_prefix('\u{feff}') {
+            Some(string) => self.str_ref(string),
+            None => self.clone(),
         }
     }
-}
-
-impl<S: Data<String>> Deref for ImString<S> {
-    type Target = str;
 
-    fn deref(&self) -> &Self::Target {
-        self.as_str()
+    /// Consumes this string and leaks it, returning a `&'static str` to its contents.
+    ///
+    /// This intentionally leaks memory: the returned string is never freed. It is meant for
+    /// strings which need to live for the rest of the program, for example when passing them to
+    /// APIs which require a `&'static str`. This mirrors [`String::leak()`].
+    ///
+    /// If this string has no other clones, the backing buffer is leaked directly, including any
+    /// spare capacity. Otherwise, the contents are copied into a new buffer first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("hello, world");
+    /// let leaked: &'static str = string.slice(..5).leak();
+    /// assert_eq!(leaked, "hello");
+    /// ```
+    pub fn leak(self) -> &'static str {
+        self.into_std_string().leak()
     }
-}
 
-impl<S: Data<String>> DerefMut for ImString<S> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        self.as_mut_str()
+    /// Splits off the first character of this string.
+    ///
+    /// Returns the first character and the rest of the string, which shares the backing string
+    /// with this one, or `None` if the string is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("ünicode");
+    /// let (first, rest) = string.split_first_char().unwrap();
+    /// assert_eq!(first, 'ü');
+    /// assert_eq!(rest, "nicode");
+    /// assert_eq!(ImString::new().split_first_char(), None);
+    /// ```
+    pub fn split_first_char(&self) -> Option<(char, Self)> {
+        let first = self.as_str().chars().next()?;
+
+        // SAFETY: the offset is the end of the first char, which is a char boundary.
+        let rest = unsafe { self.slice_unchecked(first.len_utf8()..) };
+        Some((first, rest))
     }
-}
 
-impl<S: Data<String>> Borrow<str> for ImString<S> {
-    fn borrow(&self) -> &str {
-        self.as_str()
+    /// Splits off the last character of this string.
+    ///
+    /// Returns the front of the string, which shares the backing string with this one, and the
+    /// last character, or `None` if the string is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("naïve");
+    /// let (front, last) = string.split_last_char().unwrap();
+    /// assert_eq!(front, "naïv");
+    /// assert_eq!(last, 'e');
+    /// assert_eq!(ImString::new().split_last_char(), None);
+    /// ```
+    pub fn split_last_char(&self) -> Option<(Self, char)> {
+        let last = self.as_str().chars().next_back()?;
+
+        // SAFETY: the offset is the start of the last char, which is a char boundary.
+        let front = unsafe { self.slice_unchecked(..self.len() - last.len_utf8()) };
+        Some((front, last))
     }
-}
 
-impl<S: Data<String>> BorrowMut<str> for ImString<S> {
-    fn borrow_mut(&mut self) -> &mut str {
-        self.as_mut_str()
+    /// Replaces several patterns at once, in a single left-to-right scan.
+    ///
+    /// At every position, the pairs are tried in order and the first one whose `from` pattern
+    /// matches is applied. This means that if patterns overlap, the one listed first wins, so
+    /// longer patterns should be listed before their prefixes. Replaced text is never scanned
+    /// again, so the replacements cannot affect each other. Empty `from` patterns are ignored.
+    ///
+    /// If no pattern matches, this returns a clone which shares the backing string, without
+    /// copying.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("<a href=\"x\">&</a>");
+    /// let escaped = string.replace_all(&[
+    ///     ("&", "&amp;"),
+    ///     ("<", "&lt;"),
+    ///     (">", "&gt;"),
+    ///     ("\"", "&quot;"),
+    /// ]);
+    /// assert_eq!(escaped, "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;");
+    /// ```
+    pub fn replace_all(&self, pairs: &[(&str, &str)]) -> Self {
+        let string = self.as_str();
+        let mut output: Option<String> = None;
+        let mut copied = 0;
+        let mut position = 0;
+        while position < string.len() {
+            let rest = &string[position..];
+            let pair = pairs
+                .iter()
+                .find(|(from, _)| !from.is_empty() && rest.starts_with(from));
+            match pair {
+                Some((from, to)) => {
+                    let output = output.get_or_insert_with(|| String::with_capacity(string.len()));
+                    output.push_str(&string[copied..position]);
+                    output.push_str(to);
+                    position += from.len();
+                    copied = position;
+                }
+                None => {
+                    position += rest.chars().next().map(char::len_utf8).unwrap_or(1);
+                }
+            }
+        }
+
+        match output {
+            Some(mut output) => {
+                output.push_str(&string[copied..]);
+                Self::from(output)
+            }
+            None => self.clone(),
+        }
     }
-}
 
-impl<S: Data<String>> AsRef<str> for ImString<S> {
-    fn as_ref(&self) -> &str {
-        self.as_str()
+    /// Converts this string into an [`ImString`] with a different backing type.
+    ///
+    /// This copies the contents of this string into a new backing of type `O`, for example to
+    /// turn an `ImString<Local>` into an `ImString<Threadsafe>` so that it can be sent to another
+    /// thread. Only the part of the backing string which this string refers to is copied. If `O`
+    /// is the same type as `S`, this returns a clone which shares the backing string instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use imstr::string::{ImString, Local, Threadsafe};
+    ///
+    /// let local: ImString<Local> = ImString::from("hello, world");
+    /// let threadsafe: ImString<Threadsafe> = local.slice(7..).reparent();
+    /// assert_eq!(threadsafe, "world");
+    /// ```
+    pub fn reparent<O: Data<String> + 'static>(&self) -> ImString<O>
+    where
+        S: 'static,
+    {
+        match (self as &dyn Any).downcast_ref::<ImString<O>>() {
+            Some(string) => string.clone(),
+            None => ImString::from(self.as_str()),
+        }
     }
-}
 
-#[cfg(feature = "std")]
-impl<S: Data<String>> AsRef<Path> for ImString<S> {
-    fn as_ref(&self) -> &Path {
-        self.as_str().as_ref()
+    /// Returns the number of lines in this string.
+    ///
+    /// This is the number of lines [`lines()`](ImString::lines) would yield, but computed in a
+    /// single pass over the bytes, without creating the lines. A trailing newline does not start
+    /// a new line, and an empty string has no lines.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// assert_eq!(ImString::from("one\ntwo\n").line_count(), 2);
+    /// assert_eq!(ImString::from("one\ntwo").line_count(), 2);
+    /// assert_eq!(ImString::from("").line_count(), 0);
+    /// ```
+    pub fn line_count(&self) -> usize {
+        let newlines = self
+            .as_bytes()
+            .iter()
+            .filter(|byte| **byte == b'\n')
+            .count();
+        let unterminated = !self.is_empty() && !self.ends_with('\n');
+        newlines + usize::from(unterminated)
     }
-}
 
-#[cfg(feature = "std")]
-impl<S: Data<String>> AsRef<OsStr> for ImString<S> {
-    fn as_ref(&self) -> &OsStr {
-        self.as_str().as_ref()
+    /// Searches for `needle` in this string and returns the first match as a slice.
+    ///
+    /// Unlike [`try_str_ref()`](ImString::try_str_ref), which requires `needle` to point into
+    /// this string, this compares the contents, so `needle` can come from anywhere. The returned
+    /// string shares the backing string with this one. Returns `None` if `needle` does not occur
+    /// in this string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("hello, world");
+    /// let needle = String::from("world");
+    /// assert_eq!(string.try_str_ref(&needle), None);
+    /// assert_eq!(string.find_slice(&needle).unwrap(), "world");
+    /// assert_eq!(string.find_slice("moon"), None);
+    /// ```
+    pub fn find_slice(&self, needle: &str) -> Option<Self> {
+        let start = self.as_str().find(needle)?;
+
+        // SAFETY: the match starts and ends on char boundaries, because needle is valid UTF-8.
+        Some(unsafe { self.slice_unchecked(start..start + needle.len()) })
     }
-}
 
-impl<S: Data<String>> AsRef<[u8]> for ImString<S> {
-    fn as_ref(&self) -> &[u8] {
-        self.as_str().as_ref()
+    /// Returns a copy of this string with the first `n` characters moved to the end.
+    ///
+    /// If `n` is larger than the number of characters, it wraps around, so rotating by the
+    /// number of characters returns the same contents. This always allocates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("äbcde");
+    /// assert_eq!(string.rotate_left_chars(2), "cdeäb");
+    /// assert_eq!(string.rotate_left_chars(7), "cdeäb");
+    /// ```
+    pub fn rotate_left_chars(&self, n: usize) -> Self {
+        let count = self.as_str().chars().count();
+        if count == 0 {
+            return Self::new();
+        }
+
+        let index = self
+            .as_str()
+            .char_indices()
+            .nth(n % count)
+            .map(|(index, _)| index)
+            .unwrap_or(0);
+        let (front, back) = self.as_str().split_at(index);
+        let mut string = String::with_capacity(self.len());
+        string.push_str(back);
+        string.push_str(front);
+        Self::from(string)
     }
-}
 
-impl<S: Data<String>> AsMut<str> for ImString<S> {
-    fn as_mut(&mut self) -> &mut str {
-        self.as_mut_str()
+    /// Returns a copy of this string with the last `n` characters moved to the start.
+    ///
+    /// If `n` is larger than the number of characters, it wraps around, so rotating by the
+    /// number of characters returns the same contents. This always allocates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("abcdé");
+    /// assert_eq!(string.rotate_right_chars(2), "déabc");
+    /// assert_eq!(string.rotate_right_chars(5), "abcdé");
+    /// ```
+    pub fn rotate_right_chars(&self, n: usize) -> Self {
+        let count = self.as_str().chars().count();
+        if count == 0 {
+            return Self::new();
+        }
+
+        self.rotate_left_chars(count - n % count)
     }
-}
 
-#[cfg(feature = "std")]
-impl<S: Data<String>> ToSocketAddrs for ImString<S> {
-    type Iter = <String as ToSocketAddrs>::Iter;
-    fn to_socket_addrs(&self) -> std::io::Result<<String as ToSocketAddrs>::Iter> {
-        self.as_str().to_socket_addrs()
+    /// Returns the [`char`] which starts at `byte_index`.
+    ///
+    /// Returns `None` if `byte_index` is out of bounds or does not lie on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("añb");
+    /// assert_eq!(string.char_at(1), Some('ñ'));
+    /// assert_eq!(string.char_at(2), None);
+    /// assert_eq!(string.char_at(4), None);
+    /// ```
+    pub fn char_at(&self, byte_index: usize) -> Option<char> {
+        self.as_str().get(byte_index..)?.chars().next()
     }
-}
 
-impl<S: Data<String>> Add<&str> for ImString<S> {
-    type Output = ImString<S>;
-    fn add(mut self, string: &str) -> Self::Output {
-        self.push_str(string);
-        self
+    /// Returns the [`char`] which starts at `byte_index`, or `default`.
+    ///
+    /// This is like [`char_at()`](ImString::char_at), but returns `default` if `byte_index` is out
+    /// of bounds or does not lie on a char boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("añb");
+    /// assert_eq!(string.char_at_or(3, '?'), 'b');
+    /// assert_eq!(string.char_at_or(2, '?'), '?');
+    /// assert_eq!(string.char_at_or(4, '?'), '?');
+    /// ```
+    pub fn char_at_or(&self, byte_index: usize, default: char) -> char {
+        self.char_at(byte_index).unwrap_or(default)
     }
-}
 
-impl<S: Data<String>> AddAssign<&str> for ImString<S> {
-    fn add_assign(&mut self, string: &str) {
-        self.push_str(string);
+    /// Collects the [`char`]s of this string into a [`Vec`].
+    ///
+    /// This is useful for algorithms which need random access to the characters. The vector is
+    /// allocated once, using the size hint of the characters iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("añb");
+    /// assert_eq!(string.chars_vec(), vec!['a', 'ñ', 'b']);
+    /// ```
+    pub fn chars_vec(&self) -> Vec<char> {
+        self.as_str().chars().collect()
     }
-}
 
-impl<S: Data<String>> Extend<char> for ImString<S> {
-    fn extend<T: IntoIterator<Item = char>>(&mut self, iter: T) {
+    /// Splits this string at the first character for which `predicate` returns `true`.
+    ///
+    /// Returns the part before the matching character and the rest of the string starting with
+    /// it, both of which share the backing string with this one, or `None` if no character
+    /// matches. This works like the `split_at_position` method of nom, but does not need the
+    /// `nom` feature. Unlike [`take_while_char()`](ImString::take_while_char), the predicate
+    /// selects the position to split at rather than the characters to keep.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("key value");
+    /// let (key, rest) = string.split_at_first(char::is_whitespace).unwrap();
+    /// assert_eq!(key, "key");
+    /// assert_eq!(rest, " value");
+    /// assert_eq!(string.split_at_first(|c| c == '='), None);
+    /// ```
+    pub fn split_at_first<F: Fn(char) -> bool>(&self, predicate: F) -> Option<(Self, Self)> {
+        let position = self.as_str().find(predicate)?;
+
+        // SAFETY: find() returns the byte offset of a char, which is always a char boundary.
         unsafe {
-            self.unchecked_append(|mut string| {
-                string.extend(iter);
-                string
-            });
+            Some((
+                self.slice_unchecked(..position),
+                self.slice_unchecked(position..),
+            ))
         }
     }
-}
 
-impl<'a, S: Data<String>> Extend<&'a char> for ImString<S> {
-    fn extend<T: IntoIterator<Item = &'a char>>(&mut self, iter: T) {
-        unsafe {
-            self.unchecked_append(|mut string| {
-                string.extend(iter);
-                string
-            });
-        }
+    /// Returns the byte offsets of every occurrence of `byte` in this string.
+    ///
+    /// The offsets are relative to this string and in ascending order. If `byte` is ASCII, every
+    /// offset lies on a [`char`] boundary, so it can be used to slice the string. This is useful
+    /// for building an index, such as the offsets of all newlines. When the `memchr` feature is
+    /// enabled, the occurrences are found with `memchr`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("one\ntwo\nthree\n");
+    /// assert_eq!(string.byte_positions(b'\n'), [3, 7, 13]);
+    /// assert_eq!(string.byte_positions(b'!'), []);
+    /// ```
+    pub fn byte_positions(&self, byte: u8) -> Vec<usize> {
+        #[cfg(feature = "memchr")]
+        let positions = memchr::memchr_iter(byte, self.as_bytes()).collect();
+        #[cfg(not(feature = "memchr"))]
+        let positions = self
+            .as_bytes()
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| **b == byte)
+            .map(|(position, _)| position)
+            .collect();
+        positions
     }
-}
 
-impl<'a, S: Data<String>> Extend<&'a str> for ImString<S> {
-    fn extend<T: IntoIterator<Item = &'a str>>(&mut self, iter: T) {
+    /// Splits this string at the first occurrence of `c`, removing it.
+    ///
+    /// Returns the parts before and after the separator, both of which share the backing string
+    /// with this one, or `None` if `c` does not occur. This is the same as
+    /// [`str::split_once()`] with a [`char`] pattern. When the `memchr` feature is enabled and `c`
+    /// is ASCII, the separator is found with `find_byte()`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("key=value=more");
+    /// let (key, value) = string.split_once_char('=').unwrap();
+    /// assert_eq!(key, "key");
+    /// assert_eq!(value, "value=more");
+    /// assert_eq!(string.split_once_char(':'), None);
+    /// ```
+    pub fn split_once_char(&self, c: char) -> Option<(Self, Self)> {
+        #[cfg(feature = "memchr")]
+        let position = if c.is_ascii() {
+            self.find_byte(c as u8)
+        } else {
+            self.as_str().find(c)
+        };
+        #[cfg(not(feature = "memchr"))]
+        let position = self.as_str().find(c);
+        let position = position?;
+
+        // SAFETY: the separator is a char starting at position, so position and the end of the
+        // separator are both char boundaries.
         unsafe {
-            self.unchecked_append(|mut string| {
-                string.extend(iter);
-                string
-            });
+            Some((
+                self.slice_unchecked(..position),
+                self.slice_unchecked(position + c.len_utf8()..),
+            ))
         }
     }
-}
 
-impl<S: Data<String>> FromIterator<char> for ImString<S> {
-    fn from_iter<T: IntoIterator<Item = char>>(iter: T) -> Self {
-        let mut string = ImString::new();
-        string.extend(iter);
-        string
+    /// Splits off the first whitespace-delimited word of this string.
+    ///
+    /// Leading whitespace is skipped. Returns the first word and the rest of the string after it,
+    /// with its leading whitespace removed, both of which share the backing string with this one.
+    /// If there is only one word, the rest is empty. Returns `None` if the string is empty or
+    /// contains only whitespace. This is useful for parsing commands.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("  cmd  arg1 arg2");
+    /// let (command, args) = string.split_first_word().unwrap();
+    /// assert_eq!(command, "cmd");
+    /// assert_eq!(args, "arg1 arg2");
+    /// assert_eq!(ImString::from(" \t").split_first_word(), None);
+    /// ```
+    pub fn split_first_word(&self) -> Option<(Self, Self)> {
+        let trimmed = self.trim_start();
+        if trimmed.is_empty() {
+            return None;
+        }
+
+        let (word, rest) = trimmed.take_while_char(|c| !c.is_whitespace());
+        Some((word, rest.trim_start()))
     }
-}
 
-impl<'a, S: Data<String>> FromIterator<&'a char> for ImString<S> {
-    fn from_iter<T: IntoIterator<Item = &'a char>>(iter: T) -> Self {
-        let mut string = ImString::new();
-        string.extend(iter);
-        string
+    /// Returns the 1-based line and column of the character at `byte_offset`.
+    ///
+    /// The column is counted in [`char`]s. A newline belongs to the line it terminates, so
+    /// the offset right after a newline is at column 1 of the next line. The offset
+    /// [`len()`](ImString::len) is allowed and refers to the position after the last character.
+    /// This is useful for reporting errors in parsers, and gives the same result as the position
+    /// reported by the `peg` integration.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte_offset` is greater than the length of this string or does not lie on a
+    /// [`char`] boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("first\nsécond");
+    /// assert_eq!(string.line_col_at(0), (1, 1));
+    /// assert_eq!(string.line_col_at(5), (1, 6));
+    /// assert_eq!(string.line_col_at(6), (2, 1));
+    /// assert_eq!(string.line_col_at(9), (2, 3));
+    /// ```
+    pub fn line_col_at(&self, byte_offset: usize) -> (usize, usize) {
+        let before = &self.as_str()[..byte_offset];
+        let line = before.bytes().filter(|byte| *byte == b'\n').count() + 1;
+        let column = before.chars().rev().take_while(|c| *c != '\n').count() + 1;
+        (line, column)
     }
-}
 
-impl<'a, S: Data<String>> FromIterator<&'a str> for ImString<S> {
-    fn from_iter<T: IntoIterator<Item = &'a str>>(iter: T) -> Self {
-        let mut string = ImString::new();
-        string.extend(iter);
-        string
+    /// Converts a range of [`char`] indices into the corresponding range of byte offsets.
+    ///
+    /// This is useful for interacting with APIs which report positions in characters rather than
+    /// in bytes, such as some tokenizers. The returned range is relative to this string and can
+    /// be passed to [`slice()`](ImString::slice). Returns `None` if the range is reversed or
+    /// extends past the number of characters in this string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("grüße");
+    /// assert_eq!(string.char_range_to_byte_range(2..4), Some(2..6));
+    /// assert_eq!(string.char_range_to_byte_range(0..5), Some(0..7));
+    /// assert_eq!(string.char_range_to_byte_range(4..6), None);
+    /// ```
+    pub fn char_range_to_byte_range(&self, chars: Range<usize>) -> Option<Range<usize>> {
+        if chars.start > chars.end {
+            return None;
+        }
+        let mut offsets = self
+            .as_str()
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain(core::iter::once(self.len()));
+        let start = offsets.nth(chars.start)?;
+        let end = if chars.is_empty() {
+            start
+        } else {
+            offsets.nth(chars.end - chars.start - 1)?
+        };
+        Some(start..end)
+    }
+
+    /// Returns an iterator over the byte ranges of all non-overlapping matches of `pat`.
+    ///
+    /// The ranges are relative to this string, so they can be passed directly to
+    /// [`slice()`](ImString::slice). Matches are found from left to right, in the same way as
+    /// [`str::match_indices`]. An empty pattern matches at every [`char`] boundary.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("abcXYZabc");
+    /// let ranges: Vec<_> = string.match_ranges("abc").collect();
+    /// assert_eq!(ranges, [0..3, 6..9]);
+    /// ```
+    pub fn match_ranges<'a>(&'a self, pat: &'a str) -> impl Iterator<Item = Range<usize>> + 'a {
+        self.as_str()
+            .match_indices(pat)
+            .map(|(index, matched)| index..index + matched.len())
+    }
+
+    /// Returns a wrapper which displays at most `max_chars` characters of this string.
+    ///
+    /// If the string is longer than `max_chars` characters, the output is cut off after
+    /// `max_chars` characters and followed by `…`. This is useful for logging strings which may be
+    /// very long. The limit counts [`char`]s, not bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("hello world");
+    /// assert_eq!(string.display_elided(5).to_string(), "hello…");
+    /// assert_eq!(string.display_elided(20).to_string(), "hello world");
+    /// ```
+    pub fn display_elided(&self, max_chars: usize) -> Elided<'_, S> {
+        Elided(self, max_chars)
+    }
+
+    /// Returns the first `max_chars` characters of this string, followed by `…` if it is longer.
+    ///
+    /// If the string has at most `max_chars` characters, this returns a clone which shares the
+    /// backing string, without copying. Otherwise, the result is a new string of `max_chars`
+    /// characters and the `…`. The limit counts [`char`]s, not bytes. Use
+    /// [`display_elided()`](ImString::display_elided) to format such a preview without
+    /// allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("hello world");
+    /// assert_eq!(string.preview(5), "hello…");
+    /// assert_eq!(string.preview(20), "hello world");
+    /// ```
+    pub fn preview(&self, max_chars: usize) -> Self {
+        let index = match self.as_str().char_indices().nth(max_chars) {
+            Some((index, _)) => index,
+            None => return self.clone(),
+        };
+
+        let mut string = String::with_capacity(index + '…'.len_utf8());
+        string.push_str(&self.as_str()[..index]);
+        string.push('…');
+        Self::from(string)
+    }
+
+    /// Returns a copy of this string with its lines sorted lexicographically.
+    ///
+    /// Lines are split like [`lines()`](ImString::lines) and joined with `\n`, so `\r\n` line
+    /// endings are converted to `\n`. If this string ends with a newline, so does the result.
+    /// This always allocates a new string, use [`is_lines_sorted()`](ImString::is_lines_sorted)
+    /// to check whether sorting is necessary.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("cherry\napple\nbanana\n");
+    /// assert_eq!(string.sort_lines(), "apple\nbanana\ncherry\n");
+    /// ```
+    pub fn sort_lines(&self) -> Self {
+        let mut lines: Vec<&str> = self.as_str().lines().collect();
+        lines.sort_unstable();
+        let mut string = lines.join("\n");
+        if self.ends_with('\n') {
+            string.push('\n');
+        }
+        Self::from(string)
+    }
+
+    /// Returns a copy of this string with its lines in reverse order, like `tac`.
+    ///
+    /// Lines are split like [`lines()`](ImString::lines) and joined with `\n`, so `\r\n` line
+    /// endings are converted to `\n`. If this string ends with a newline, so does the result.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("first\nsecond\nthird\n");
+    /// assert_eq!(string.reverse_lines(), "third\nsecond\nfirst\n");
+    /// ```
+    pub fn reverse_lines(&self) -> Self {
+        let mut lines: Vec<&str> = self.as_str().lines().collect();
+        lines.reverse();
+        let mut string = lines.join("\n");
+        if self.ends_with('\n') {
+            string.push('\n');
+        }
+        Self::from(string)
+    }
+
+    /// Returns `true` if the lines of this string are sorted lexicographically.
+    ///
+    /// Lines are split like [`lines()`](ImString::lines). This does not allocate. Equal adjacent
+    /// lines count as sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// assert!(ImString::from("apple\nbanana\n").is_lines_sorted());
+    /// assert!(!ImString::from("banana\napple\n").is_lines_sorted());
+    /// ```
+    pub fn is_lines_sorted(&self) -> bool {
+        let mut lines = self.as_str().lines();
+        let mut previous = match lines.next() {
+            Some(line) => line,
+            None => return true,
+        };
+        for line in lines {
+            if line < previous {
+                return false;
+            }
+            previous = line;
+        }
+        true
+    }
+
+    /// Returns the lines of this string which are also lines of `other`.
+    ///
+    /// Lines are split like [`lines()`](ImString::lines). The lines of `other` are collected into
+    /// a set first, so this takes *O((n + m) log m)* comparisons. The returned lines are slices
+    /// of this string which share its backing string, in the order in which they appear in it.
+    /// A line which occurs several times in this string is returned every time.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let old = ImString::from("one\ntwo\nthree\n");
+    /// let new = ImString::from("zero\nthree\none\n");
+    /// assert_eq!(old.lines_in_common(&new), ["one", "three"]);
+    /// ```
+    pub fn lines_in_common(&self, other: &ImString<S>) -> Vec<Self> {
+        let other: BTreeSet<&str> = other.as_str().lines().collect();
+        self.as_str()
+            .lines()
+            .filter(|line| other.contains(line))
+            .map(|line| self.str_ref(line))
+            .collect()
+    }
+
+    /// Returns a copy of this string with consecutive duplicate lines removed, like `uniq`.
+    ///
+    /// Lines are compared like [`lines()`](ImString::lines) splits them, so a line ending in
+    /// `\r\n` equals one ending in `\n`. Of each run of equal lines, the first one is kept with
+    /// its original line ending. Duplicate lines which are not adjacent are kept. If there are no
+    /// consecutive duplicates, this returns a clone which shares the backing string, without
+    /// copying.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("a\na\nb\na\n");
+    /// assert_eq!(string.dedup_lines(), "a\nb\na\n");
+    /// ```
+    pub fn dedup_lines(&self) -> Self {
+        let mut lines = self.as_str().lines();
+        let mut previous = lines.next();
+        let duplicates = lines.any(|line| {
+            let duplicate = previous == Some(line);
+            previous = Some(line);
+            duplicate
+        });
+        if !duplicates {
+            return self.clone();
+        }
+
+        let mut string = String::with_capacity(self.len());
+        let mut previous = None;
+        for segment in self.as_str().split_inclusive('\n') {
+            let line = segment
+                .strip_suffix('\n')
+                .map(|line| line.strip_suffix('\r').unwrap_or(line))
+                .unwrap_or(segment);
+            if previous != Some(line) {
+                string.push_str(segment);
+                previous = Some(line);
+            }
+        }
+        Self::from(string)
+    }
+
+    /// Returns an iterator over the runs of content and separators in this string.
+    ///
+    /// The string is split into maximal runs of characters which are either all contained in
+    /// `separators` or all not contained in it, so content and separator runs alternate. Every
+    /// run shares the backing string with this one, and concatenating them reproduces this string
+    /// exactly. This is useful for tokenizers which need to preserve whitespace, such as syntax
+    /// highlighters.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let string = ImString::from("let x  = 1;");
+    /// let tokens: Vec<ImString> = string.split_keeping_separators(&[' ', ';']).collect();
+    /// assert_eq!(tokens, ["let", " ", "x", "  ", "=", " ", "1", ";"]);
+    /// ```
+    pub fn split_keeping_separators<'a>(
+        &'a self,
+        separators: &'a [char],
+    ) -> impl Iterator<Item = Self> + 'a {
+        let mut rest = self.clone();
+        core::iter::from_fn(move || {
+            let first = rest.as_str().chars().next()?;
+            let separator = separators.contains(&first);
+            let (run, remainder) = rest.take_while_char(|c| separators.contains(&c) == separator);
+            rest = remainder;
+            Some(run)
+        })
+    }
+
+    /// Returns an iterator over the components of this string, interpreted as a [`Path`].
+    ///
+    /// The string is split like [`Path::components()`], so the separators and the rules for
+    /// normalizing redundant separators and `.` components are those of the current platform.
+    /// On Unix, only `/` is a separator, while on Windows both `/` and `\\` are, and paths may
+    /// start with a prefix such as `C:`.
+    ///
+    /// Components whose bytes appear in this string, such as file and directory names, share the
+    /// backing string with it. Components which the standard library synthesizes instead, such as
+    /// the root directory separator, `.` and `..`, are allocated as new strings.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// # #[cfg(unix)]
+    /// # {
+    /// let path = ImString::from("/usr//lib/./libc.so");
+    /// let components: Vec<ImString> = path.path_components().collect();
+    /// assert_eq!(components, ["/", "usr", "lib", "libc.so"]);
+    /// # }
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn path_components(&self) -> impl Iterator<Item = Self> + '_ {
+        Path::new(self.as_str()).components().map(|component| {
+            // the components of a path made from a str are always valid UTF-8
+            let component = component.as_os_str().to_str().unwrap();
+            self.try_str_ref(component)
+                .unwrap_or_else(|| Self::from(component))
+        })
+    }
+
+    /// Counts how often each [`char`] occurs in this string.
+    ///
+    /// The string is traversed once. The counts add up to [`len_chars()`](ImString::len_chars).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use imstr::ImString;
+    /// let counts = ImString::from("hello").char_counts();
+    /// assert_eq!(counts[&'l'], 2);
+    /// assert_eq!(counts[&'h'], 1);
+    /// assert_eq!(counts.get(&'z'), None);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn char_counts(&self) -> HashMap<char, usize> {
+        let mut counts = HashMap::new();
+        for c in self.as_str().chars() {
+            *counts.entry(c).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+impl<S: Data<String>> Default for ImString<S> {
+    fn default() -> Self {
+        ImString::new()
+    }
+}
+
+impl<S: Data<String>> From<&str> for ImString<S> {
+    fn from(string: &str) -> Self {
+        ImString::from_std_string(string.to_string())
+    }
+}
+
+impl<S: Data<String>> From<char> for ImString<S> {
+    fn from(c: char) -> Self {
+        String::from(c).into()
+    }
+}
+
+impl<S: Data<String>> From<String> for ImString<S> {
+    fn from(string: String) -> Self {
+        ImString::from_std_string(string)
+    }
+}
+
+impl<'a, S: Data<String>> From<Cow<'a, str>> for ImString<S> {
+    fn from(string: Cow<'a, str>) -> Self {
+        match string {
+            Cow::Borrowed(string) => ImString::from(string),
+            Cow::Owned(string) => ImString::from_std_string(string),
+        }
+    }
+}
+
+impl<S: Data<String>> From<ImString<S>> for String {
+    fn from(string: ImString<S>) -> Self {
+        string.into_std_string()
+    }
+}
+
+impl<S: Data<String>> From<ImString<S>> for Box<str> {
+    fn from(string: ImString<S>) -> Self {
+        string.into_boxed_str()
+    }
+}
+
+/// Copies the bytes of an [`ImString`] into an array, if the lengths match.
+///
+/// Like the conversion of a [`Vec`] into an array, this returns the string unchanged as the error
+/// if its length in bytes is not `N`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use imstr::ImString;
+/// let magic: [u8; 4] = ImString::from("PK\x03\x04").try_into().unwrap();
+/// assert_eq!(magic, *b"PK\x03\x04");
+/// ```
+impl<S: Data<String>, const N: usize> TryFrom<ImString<S>> for [u8; N] {
+    type Error = ImString<S>;
+
+    fn try_from(string: ImString<S>) -> Result<Self, Self::Error> {
+        match string.as_bytes().try_into() {
+            Ok(bytes) => Ok(bytes),
+            Err(_) => Err(string),
+        }
+    }
+}
+
+impl<S: Data<String>> PartialEq<str> for ImString<S> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str().eq(other)
+    }
+}
+
+impl<'a, S: Data<String>> PartialEq<&'a str> for ImString<S> {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str().eq(*other)
+    }
+}
+
+impl<S: Data<String>> PartialEq<String> for ImString<S> {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str().eq(other.as_str())
+    }
+}
+
+impl<S: Data<String>, O: Data<String>> PartialEq<ImString<O>> for ImString<S> {
+    fn eq(&self, other: &ImString<O>) -> bool {
+        // clones and equal slices of the same backing string point to the same bytes, so they can
+        // be compared without looking at the contents. comparing strings of different lengths is
+        // already fast, because str comparison checks the lengths first.
+        if self.as_ptr() == other.as_ptr() && self.len() == other.len() {
+            return true;
+        }
+
+        self.as_str().eq(other.as_str())
+    }
+}
+
+impl<S: Data<String>> PartialEq<[u8]> for ImString<S> {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_bytes().eq(other)
+    }
+}
+
+impl<'a, S: Data<String>> PartialEq<&'a [u8]> for ImString<S> {
+    fn eq(&self, other: &&'a [u8]) -> bool {
+        self.as_bytes().eq(*other)
+    }
+}
+
+impl<'a, S: Data<String>> PartialEq<Cow<'a, str>> for ImString<S> {
+    fn eq(&self, other: &Cow<'a, str>) -> bool {
+        self.as_str().eq(other.as_ref())
+    }
+}
+
+impl<'a, S: Data<String>> PartialEq<ImString<S>> for Cow<'a, str> {
+    fn eq(&self, other: &ImString<S>) -> bool {
+        self.as_ref().eq(other.as_str())
+    }
+}
+
+impl<S: Data<String>> Eq for ImString<S> {}
+
+impl<S: Data<String>> PartialOrd<ImString<S>> for ImString<S> {
+    fn partial_cmp(&self, other: &ImString<S>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: Data<String>> Ord for ImString<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl<S: Data<String>> Debug for ImString<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<S: Data<String>> Display for ImString<S> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        // delegate to `str` rather than using `write_str()`, so that width, fill and alignment
+        // flags are respected.
+        Display::fmt(self.as_str(), formatter)
+    }
+}
+
+impl<S: Data<String>> FromStr for ImString<S> {
+    type Err = Infallible;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(ImString::from(s))
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::data::Cloned;
-    use alloc::boxed::Box;
-    use alloc::format;
-    use alloc::vec;
+// Delegate hash to contained str. This is important!
+impl<S: Data<String>> Hash for ImString<S> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.as_str().hash(hasher)
+    }
+}
+
+impl<S: Data<String>> Write for ImString<S> {
+    fn write_str(&mut self, string: &str) -> Result<(), FmtError> {
+        self.push_str(string);
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> Result<(), FmtError> {
+        self.push(c);
+        Ok(())
+    }
+}
+
+impl<S: Data<String>> Index<Range<usize>> for ImString<S> {
+    type Output = str;
+    fn index(&self, index: Range<usize>) -> &str {
+        &self.as_str()[index]
+    }
+}
+
+impl<S: Data<String>> Index<RangeFrom<usize>> for ImString<S> {
+    type Output = str;
+    fn index(&self, index: RangeFrom<usize>) -> &str {
+        &self.as_str()[index]
+    }
+}
+
+impl<S: Data<String>> Index<RangeFull> for ImString<S> {
+    type Output = str;
+    fn index(&self, _index: RangeFull) -> &str {
+        self.as_str()
+    }
+}
+
+impl<S: Data<String>> Index<RangeInclusive<usize>> for ImString<S> {
+    type Output = str;
+    fn index(&self, index: RangeInclusive<usize>) -> &str {
+        &self.as_str()[index]
+    }
+}
+
+impl<S: Data<String>> Index<RangeTo<usize>> for ImString<S> {
+    type Output = str;
+    fn index(&self, index: RangeTo<usize>) -> &str {
+        &self.as_str()[index]
+    }
+}
+
+impl<S: Data<String>> IndexMut<Range<usize>> for ImString<S> {
+    fn index_mut(&mut self, index: Range<usize>) -> &mut str {
+        &mut self.as_mut_str()[index]
+    }
+}
+
+impl<S: Data<String>> IndexMut<RangeFrom<usize>> for ImString<S> {
+    fn index_mut(&mut self, index: RangeFrom<usize>) -> &mut str {
+        &mut self.as_mut_str()[index]
+    }
+}
+
+impl<S: Data<String>> IndexMut<RangeFull> for ImString<S> {
+    fn index_mut(&mut self, index: RangeFull) -> &mut str {
+        &mut self.as_mut_str()[index]
+    }
+}
+
+impl<S: Data<String>> IndexMut<RangeInclusive<usize>> for ImString<S> {
+    fn index_mut(&mut self, index: RangeInclusive<usize>) -> &mut str {
+        &mut self.as_mut_str()[index]
+    }
+}
+
+impl<S: Data<String>> IndexMut<RangeTo<usize>> for ImString<S> {
+    fn index_mut(&mut self, index: RangeTo<usize>) -> &mut str {
+        &mut self.as_mut_str()[index]
+    }
+}
+
+/// Iterator over lines of an [`ImString`].
+///
+/// Unlike the [`Lines`](std::str::Lines) iterator of [`str`], this iterator returns instances of
+/// [`ImString`].
+///
+/// # Example
+///
+/// ```rust
+/// # use imstr::ImString;
+/// let string = ImString::from("multi\nline\ninput");
+/// let lines: Vec<ImString> = string.lines().collect();
+/// assert_eq!(lines[0], "multi");
+/// assert_eq!(lines[1], "line");
+/// assert_eq!(lines[2], "input");
+/// ```
+pub type Lines<'a, S> = ImStringIterator<'a, S, core::str::Lines<'a>>;
+
+/// Iterator wrapper over string slices of an [`ImString`].
+///
+/// This iterator wrapper turns string slices of an [`ImString`] into [`ImString`]s.
+pub struct ImStringIterator<'a, S: Data<String>, I: Iterator<Item = &'a str>> {
+    string: S,
+    iterator: I,
+}
+
+impl<'a, S: Data<String>, I: Iterator<Item = &'a str>> Iterator for ImStringIterator<'a, S, I> {
+    type Item = ImString<S>;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.iterator.next() {
+            Some(slice) => {
+                let offset =
+                    try_slice_offset(self.string.get().as_bytes(), slice.as_bytes()).unwrap();
+                Some(ImString {
+                    string: self.string.clone(),
+                    offset,
+                })
+            }
+            None => None,
+        }
+    }
+}
+
+impl<'a, S: Data<String>, I: Iterator<Item = &'a str>> ImStringIterator<'a, S, I> {
+    fn new(string: S, iterator: I) -> Self {
+        ImStringIterator { string, iterator }
+    }
+}
+
+/// Iterator over `char`s with their corresponding byte index inside an `ImString`.
+///
+/// The [`Debug`] output shows the byte index of the next `char` and the remaining string, for
+/// example `CharIndices { offset: 1, remaining: "bc" }`.
+#[derive(Clone)]
+pub struct CharIndices<S: Data<String>> {
+    offset: usize,
+    string: ImString<S>,
+}
+
+impl<S: Data<String>> Debug for CharIndices<S> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        formatter
+            .debug_struct("CharIndices")
+            .field("offset", &self.offset)
+            .field("remaining", &self.string.as_str())
+            .finish()
+    }
+}
+
+impl<S: Data<String>> Iterator for CharIndices<S> {
+    type Item = (usize, char);
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.string.as_str().chars().next() {
+            Some(c) => {
+                let len = c.len_utf8();
+                self.string = self.string.slice(len..);
+                let offset = self.offset;
+                self.offset += len;
+                Some((offset, c))
+            }
+            None => None,
+        }
+    }
+}
+
+/// Iterator over `char`s inside an `ImString`.
+///
+/// Like the [`Chars`](core::str::Chars) iterator of the standard library, the [`Debug`] output
+/// lists the remaining `char`s, for example `Chars(['b', 'c'])`.
+#[derive(Clone)]
+pub struct Chars<S: Data<String>> {
+    string: ImString<S>,
+}
+
+impl<S: Data<String>> Debug for Chars<S> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        Debug::fmt(&self.string.as_str().chars(), formatter)
+    }
+}
+
+impl<S: Data<String>> Iterator for Chars<S> {
+    type Item = char;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.string.as_str().chars().next() {
+            Some(c) => {
+                self.string = self.string.slice(c.len_utf8()..);
+                Some(c)
+            }
+            None => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.string.as_str().chars().size_hint()
+    }
+}
+
+/// Wrapper which displays an [`ImString`], eliding it after a maximum number of `char`s.
+///
+/// This is returned by [`ImString::display_elided()`].
+#[derive(Clone, Copy)]
+pub struct Elided<'a, S: Data<String>>(&'a ImString<S>, usize);
+
+impl<S: Data<String>> Debug for Elided<'_, S> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        formatter
+            .debug_tuple("Elided")
+            .field(&self.0.as_str())
+            .field(&self.1)
+            .finish()
+    }
+}
+
+impl<S: Data<String>> Display for Elided<'_, S> {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> Result<(), FmtError> {
+        let string = self.0.as_str();
+        match string.char_indices().nth(self.1) {
+            Some((index, _)) => {
+                formatter.write_str(&string[..index])?;
+                formatter.write_str("…")
+            }
+            None => formatter.write_str(string),
+        }
+    }
+}
+
+/// Terminator of a line, as returned by [`ImString::lines_with_endings()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LineEnding {
+    /// Line was terminated by `\n`.
+    Lf,
+    /// Line was terminated by `\r\n`.
+    CrLf,
+    /// Line was not terminated, because it is the last line of the string.
+    None,
+}
+
+impl LineEnding {
+    /// Returns the terminator as a string, which is empty for [`LineEnding::None`].
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::None => "",
+        }
+    }
+}
+
+impl<S: Data<String>> Deref for ImString<S> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.as_str()
+    }
+}
+
+impl<S: Data<String>> DerefMut for ImString<S> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.as_mut_str()
+    }
+}
+
+impl<S: Data<String>> Borrow<str> for ImString<S> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<S: Data<String>> BorrowMut<str> for ImString<S> {
+    fn borrow_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl<S: Data<String>> AsRef<str> for ImString<S> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: Data<String>> AsRef<Path> for ImString<S> {
+    fn as_ref(&self) -> &Path {
+        self.as_str().as_ref()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: Data<String>> AsRef<OsStr> for ImString<S> {
+    fn as_ref(&self) -> &OsStr {
+        self.as_str().as_ref()
+    }
+}
+
+impl<S: Data<String>> AsRef<[u8]> for ImString<S> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_str().as_ref()
+    }
+}
+
+impl<S: Data<String>> AsMut<str> for ImString<S> {
+    fn as_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<S: Data<String>> ToSocketAddrs for ImString<S> {
+    type Iter = <String as ToSocketAddrs>::Iter;
+    fn to_socket_addrs(&self) -> std::io::Result<<String as ToSocketAddrs>::Iter> {
+        self.as_str().to_socket_addrs()
+    }
+}
+
+impl<S: Data<String>> Add<&str> for ImString<S> {
+    type Output = ImString<S>;
+    fn add(mut self, string: &str) -> Self::Output {
+        self.push_str(string);
+        self
+    }
+}
+
+impl<S: Data<String>> AddAssign<&str> for ImString<S> {
+    fn add_assign(&mut self, string: &str) {
+        self.push_str(string);
+    }
+}
+
+impl<S: Data<String>> Extend<char> for ImString<S> {
+    fn extend<T: IntoIterator<Item = char>>(&mut self, iter: T) {
+        unsafe {
+            self.unchecked_append(|mut string| {
+                string.extend(iter);
+                string
+            });
+        }
+    }
+}
+
+impl<'a, S: Data<String>> Extend<&'a char> for ImString<S> {
+    fn extend<T: IntoIterator<Item = &'a char>>(&mut self, iter: T) {
+        unsafe {
+            self.unchecked_append(|mut string| {
+                string.extend(iter);
+                string
+            });
+        }
+    }
+}
+
+impl<'a, S: Data<String>> Extend<&'a str> for ImString<S> {
+    fn extend<T: IntoIterator<Item = &'a str>>(&mut self, iter: T) {
+        unsafe {
+            self.unchecked_append(|mut string| {
+                string.extend(iter);
+                string
+            });
+        }
+    }
+}
+
+impl<S: Data<String>> FromIterator<char> for ImString<S> {
+    fn from_iter<T: IntoIterator<Item = char>>(iter: T) -> Self {
+        let mut string = ImString::new();
+        string.extend(iter);
+        string
+    }
+}
+
+impl<'a, S: Data<String>> FromIterator<&'a char> for ImString<S> {
+    fn from_iter<T: IntoIterator<Item = &'a char>>(iter: T) -> Self {
+        let mut string = ImString::new();
+        string.extend(iter);
+        string
+    }
+}
+
+impl<'a, S: Data<String>> FromIterator<&'a str> for ImString<S> {
+    fn from_iter<T: IntoIterator<Item = &'a str>>(iter: T) -> Self {
+        let mut string = ImString::new();
+        string.extend(iter);
+        string
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    dropping_references,
+    clippy::iter_nth_zero,
+    clippy::len_zero,
+    clippy::redundant_slicing,
+    clippy::useless_format
+)]
+mod tests {
+    use super::*;
+    use crate::data::Cloned;
+    use alloc::boxed::Box;
+    use alloc::format;
+    use alloc::vec;
+
+    fn test_strings<S: Data<String>>() -> Vec<ImString<S>> {
+        let long = ImString::from("long string here");
+        let world = ImString::from("world");
+        let some = ImString::from("some");
+        let multiline = ImString::from("some\nmulti\nline\nstring\nthat\nis\nlong");
+        let large: ImString<S> = (0..100).map(|_| "hello\n").collect();
+        vec![
+            ImString::new(),
+            ImString::default(),
+            ImString::from(""),
+            ImString::from("a"),
+            ImString::from("ü"),
+            ImString::from("hello"),
+            ImString::from("0.0.0.0:800"),
+            ImString::from("localhost:1234"),
+            ImString::from("0.0.0.0:1234"),
+            large.slice(0..6),
+            large.slice(6..12),
+            large.slice(..),
+            long.clone(),
+            long.slice(4..10),
+            long.slice(0..4),
+            long.slice(4..4),
+            long.slice(5..),
+            long.slice(..),
+            world.clone(),
+            world.clone(),
+            some.slice(4..),
+            some,
+            multiline.slice(5..15),
+            multiline,
+            ImString::from("\u{e4}\u{fc}\u{f6}\u{f8}\u{3a9}"),
+            ImString::from("\u{1f600}\u{1f603}\u{1f604}"),
+            ImString::from("o\u{308}u\u{308}a\u{308}"),
+        ]
+    }
+
+    macro_rules! tests {
+        () => {};
+        (#[test] fn $name:ident <S: Data<String>>() $body:tt $($rest:tt)*) => {
+            #[test]
+            fn $name() {
+                fn $name <S: Data<String>>() $body
+                $name::<Threadsafe>();
+                $name::<Local>();
+                $name::<Cloned<String>>();
+                $name::<Box<String>>();
+                #[cfg(feature = "triomphe")]
+                $name::<triomphe::Arc<String>>();
+            }
+            tests!{$($rest)*}
+        };
+        (#[test] fn $name:ident <S: Data<String>>($string:ident: ImString<S>) $body:tt $($rest:tt)*) => {
+            #[test]
+            fn $name() {
+                fn $name <S: Data<String>>() {
+                    fn $name <S: Data<String>>($string: ImString<S>) $body
+                    for string in test_strings::<S>().into_iter() {
+                        $name(string);
+                    }
+                }
+                $name::<Threadsafe>();
+                $name::<Local>();
+                $name::<Cloned<String>>();
+                $name::<Box<String>>();
+                #[cfg(feature = "triomphe")]
+                $name::<triomphe::Arc<String>>();
+            }
+            tests!{$($rest)*}
+        }
+    }
+
+    tests! {
+        #[test]
+        fn test_new<S: Data<String>>() {
+            let string: ImString<S> = ImString::new();
+            assert_eq!(string.string.get().len(), 0);
+            assert_eq!(string.offset, 0..0);
+        }
+
+        #[test]
+        fn test_default<S: Data<String>>() {
+            let string: ImString<S> = ImString::new();
+            assert_eq!(string.string.get().len(), 0);
+            assert_eq!(string.offset, 0..0);
+        }
+
+        #[test]
+        fn test_with_capacity<S: Data<String>>() {
+            for capacity in [10, 100, 256] {
+                let string: ImString<S> = ImString::with_capacity(capacity);
+                assert!(string.capacity() >= capacity);
+                assert_eq!(string.string.get().len(), 0);
+                assert_eq!(string.offset, 0..0);
+            }
+        }
+
+        #[test]
+        fn test_offset<S: Data<String>>(string: ImString<S>) {
+            assert!(string.offset.start <= string.string.get().len());
+            assert!(string.offset.start <= string.offset.end);
+            assert!(string.offset.end <= string.string.get().len());
+        }
+
+        #[test]
+        fn test_as_str<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.as_str(), &string.string.get()[string.offset.clone()]);
+            assert_eq!(string.as_str().len(), string.len());
+        }
+
+        #[test]
+        fn test_as_mut_str<S: Data<String>>(string: ImString<S>) {
+            // ascii uppercase copy of string
+            let string_uppercase = string.as_str().to_ascii_uppercase();
+
+            // uppercase in-place
+            let mut string = string;
+            let string_slice = string.as_mut_str();
+            string_slice.make_ascii_uppercase();
+
+            // make sure both versions are identical
+            assert_eq!(string_uppercase, &*string_slice);
+            drop(string_slice);
+            assert_eq!(string, string_uppercase);
+        }
+
+        #[test]
+        fn test_as_bytes<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.as_bytes(), &string.string.get().as_bytes()[string.offset.clone()]);
+            assert_eq!(string.as_bytes().len(), string.len());
+        }
+
+        #[test]
+        fn test_len<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.len(), string.offset.len());
+            assert_eq!(string.len(), string.as_str().len());
+            assert_eq!(string.len(), string.as_bytes().len());
+        }
+
+        #[test]
+        fn test_clear<S: Data<String>>(string: ImString<S>) {
+            let mut string = string;
+            string.clear();
+            assert_eq!(string.as_str(), "");
+            assert_eq!(string.len(), 0);
+        }
+
+        #[test]
+        fn test_debug<S: Data<String>>(string: ImString<S>) {
+            let debug_string = format!("{string:?}");
+            let debug_str = format!("{:?}", string.as_str());
+            assert_eq!(debug_string, debug_str);
+        }
+
+        #[test]
+        fn test_deref<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.deref(), string.as_str());
+        }
+
+        #[test]
+        fn test_clone<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string, string.clone());
+        }
+
+        #[test]
+        fn test_display<S: Data<String>>(string: ImString<S>) {
+            let display_string = format!("{string}");
+            let display_str = format!("{}", string.as_str());
+            assert_eq!(display_string, display_str);
+        }
+
+        #[test]
+        fn test_display_padding<S: Data<String>>(string: ImString<S>) {
+            let str = string.as_str();
+            assert_eq!(format!("{string:>10}"), format!("{str:>10}"));
+            assert_eq!(format!("{string:<10}"), format!("{str:<10}"));
+            assert_eq!(format!("{string:^10}"), format!("{str:^10}"));
+            assert_eq!(format!("{string:*^10}"), format!("{str:*^10}"));
+            assert_eq!(format!("{string:.3}"), format!("{str:.3}"));
+        }
+
+        #[test]
+        fn test_display_alignment<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("hi");
+            assert_eq!(format!("{string:>6}"), "    hi");
+            assert_eq!(format!("{string:<6}"), "hi    ");
+            assert_eq!(format!("{string:^6}"), "  hi  ");
+            assert_eq!(format!("{string:-^7}"), "--hi---");
+            assert_eq!(format!("{string:_>width$}", width = 4), "__hi");
+            assert_eq!(format!("{string:1}"), "hi");
+        }
+
+        #[test]
+        fn test_insert_start<S: Data<String>>(string: ImString<S>) {
+            let mut string = string;
+            let length = string.len();
+            string.insert(0, 'h');
+            assert_eq!(string.len(), length + 1);
+            assert_eq!(string.chars().nth(0), Some('h'));
+        }
+
+        #[test]
+        fn test_insert_one<S: Data<String>>(string: ImString<S>) {
+            if !string.is_empty() && string.is_char_boundary(1) {
+                let mut string = string;
+                let length = string.len();
+                string.insert(1, 'h');
+                assert_eq!(string.len(), length + 1);
+                assert_eq!(string.chars().nth(1), Some('h'));
+            }
+        }
+
+        #[test]
+        fn test_insert_end<S: Data<String>>(string: ImString<S>) {
+            let mut string = string;
+            let length = string.len();
+            string.insert(length, 'h');
+            assert_eq!(string.len(), length + 1);
+            // FIXME
+            //assert_eq!(string.chars().nth(length), Some('h'));
+        }
+
+        #[test]
+        fn test_insert_str_start<S: Data<String>>(string: ImString<S>) {
+            // test to make sure we can insert_str at the start
+            let original = string.as_str().to_string();
+            let mut string = string;
+            let inserted = "hello";
+            string.insert_str(0, inserted);
+            assert_eq!(string.len(), original.len() + inserted.len());
+            assert_eq!(&string[0..inserted.len()], inserted);
+            assert_eq!(&string[inserted.len()..], original);
+        }
+
+        #[test]
+        fn test_insert_str_end<S: Data<String>>(string: ImString<S>) {
+            // test to make sure we can insert_str at the end
+            let original = string.as_str().to_string();
+            let mut string = string;
+            let inserted = "hello";
+            string.insert_str(string.len(), inserted);
+            assert_eq!(string.len(), original.len() + inserted.len());
+            assert_eq!(&string[0..original.len()], original);
+            assert_eq!(&string[original.len()..], inserted);
+        }
+
+        #[test]
+        fn test_is_empty<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.is_empty(), string.len() == 0);
+            assert_eq!(string.is_empty(), string.as_str().is_empty());
+        }
+
+        #[test]
+        fn test_push<S: Data<String>>(string: ImString<S>) {
+            let mut string = string;
+            let mut std_string = string.as_str().to_string();
+            let c = 'c';
+            std_string.push(c);
+            string.push(c);
+            assert_eq!(string, std_string);
+        }
+
+        #[test]
+        fn test_push_str<S: Data<String>>(string: ImString<S>) {
+            let mut string = string;
+            let mut std_string = string.as_str().to_string();
+            let s = "string";
+            std_string.push_str(s);
+            string.push_str(s);
+            assert_eq!(string, std_string);
+        }
+
+        #[test]
+        fn test_pop<S: Data<String>>(string: ImString<S>) {
+            let mut characters: Vec<char> = string.chars().collect();
+            let mut string = string;
+            loop {
+                let c1 = characters.pop();
+                let c2 = string.pop();
+                assert_eq!(c1, c2);
+                if c1.is_none() {
+                    break;
+                }
+            }
+        }
+
+        #[test]
+        fn test_index_range_full<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(&string[..], &string.as_str()[..]);
+        }
+
+        #[test]
+        fn test_index_range_from<S: Data<String>>(string: ImString<S>) {
+            for i in (0..string.len()).filter(|i| string.is_char_boundary(*i)) {
+                assert_eq!(&string[i..], &string.as_str()[i..]);
+            }
+        }
+
+        #[test]
+        fn test_index_range_to<S: Data<String>>(string: ImString<S>) {
+            for i in (0..string.len()).filter(|i| string.is_char_boundary(*i)) {
+                assert_eq!(&string[..i], &string.as_str()[..i]);
+            }
+        }
+
+        #[test]
+        fn test_index_range_exclusive<S: Data<String>>(string: ImString<S>) {
+            for start in (0..string.len()).filter(|i| string.is_char_boundary(*i)) {
+                for end in (start..string.len()).filter(|i| string.is_char_boundary(*i)) {
+                    assert_eq!(&string[start..end], &string.as_str()[start..end]);
+                }
+            }
+        }
+
+        #[test]
+        fn test_index_range_inclusive<S: Data<String>>(string: ImString<S>) {
+            if !string.is_empty() {
+                for start in (0..string.len()-1).filter(|i| string.is_char_boundary(*i)) {
+                    for end in (start..string.len()-1).filter(|i| string.is_char_boundary(*i + 1)) {
+                        assert_eq!(&string[start..=end], &string.as_str()[start..=end]);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_into_bytes<S: Data<String>>(string: ImString<S>) {
+            let std_bytes = string.as_str().to_string().into_bytes();
+            let bytes = string.into_bytes();
+            assert_eq!(bytes, std_bytes);
+        }
+
+        #[test]
+        fn test_into_boxed_bytes<S: Data<String>>(string: ImString<S>) {
+            let std_bytes = string.as_str().to_string().into_bytes().into_boxed_slice();
+            let bytes = string.into_boxed_bytes();
+            assert_eq!(bytes, std_bytes);
+        }
+
+        #[test]
+        fn test_into_boxed_bytes_sliced<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("hello, world");
+            let bytes = string.slice(7..).into_boxed_bytes();
+            assert_eq!(&*bytes, b"world");
+            let bytes = string.slice(..5).into_boxed_bytes();
+            assert_eq!(&*bytes, b"hello");
+        }
+
+        #[test]
+        fn test_into_boxed_str<S: Data<String>>(string: ImString<S>) {
+            let expected: Box<str> = string.as_str().into();
+            let boxed: Box<str> = string.clone().into_boxed_str();
+            assert_eq!(boxed, expected);
+            let boxed: Box<str> = string.into();
+            assert_eq!(boxed, expected);
+        }
+
+        #[test]
+        fn test_into_boxed_str_generic<S: Data<String>>() {
+            fn boxed<T: Into<Box<str>>>(value: T) -> Box<str> {
+                value.into()
+            }
+
+            let string: ImString<S> = ImString::from("hello, world");
+            assert_eq!(&*boxed(string.slice(7..)), "world");
+            assert_eq!(&*Box::<str>::from(string), "hello, world");
+        }
+
+        #[test]
+        fn test_slice_all<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.slice(..), string);
+        }
+
+        #[test]
+        fn test_slice_start<S: Data<String>>(string: ImString<S>) {
+            for end in 0..string.len() {
+                if string.is_char_boundary(end) {
+                    assert_eq!(string.slice(..end), string.as_str()[..end]);
+                }
+            }
+        }
+
+        #[test]
+        fn test_slice_end<S: Data<String>>(string: ImString<S>) {
+            for start in 0..string.len() {
+                if string.is_char_boundary(start) {
+                    assert_eq!(string.slice(start..), string.as_str()[start..]);
+                }
+            }
+        }
+
+        #[test]
+        fn test_slice_middle<S: Data<String>>(string: ImString<S>) {
+            for start in 0..string.len() {
+                if string.is_char_boundary(start) {
+                    for end in start..string.len() {
+                        if string.is_char_boundary(end) {
+                            assert_eq!(string.slice(start..end), string.as_str()[start..end]);
+                        }
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn test_try_slice_all<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.try_slice(..).unwrap(), string);
+        }
+
+        #[test]
+        fn test_try_slice_start<S: Data<String>>(string: ImString<S>) {
+            for end in 0..string.len() {
+                if string.is_char_boundary(end) {
+                    assert_eq!(string.try_slice(..end).unwrap(), string.as_str()[..end]);
+                } else {
+                    // cannot get slice with end in middle of UTF-8 multibyte sequence.
+                    assert_eq!(string.try_slice(..end), Err(SliceError::EndNotAligned));
+                }
+            }
+
+            // cannot get slice with end pointing past the end of the string.
+            assert_eq!(string.try_slice(..string.len()+1), Err(SliceError::EndOutOfBounds));
+        }
+
+        #[test]
+        fn test_try_slice_end<S: Data<String>>(string: ImString<S>) {
+            for start in 0..string.len() {
+                if string.is_char_boundary(start) {
+                    assert_eq!(string.try_slice(start..).unwrap(), string.as_str()[start..]);
+                } else {
+                    // cannot get slice with end in middle of UTF-8 multibyte sequence.
+                    assert_eq!(string.try_slice(start..), Err(SliceError::StartNotAligned));
+                }
+            }
+
+            // cannot get slice with end pointing past the end of the string.
+            assert_eq!(string.try_slice(string.len()+1..), Err(SliceError::StartOutOfBounds));
+        }
+
+        #[test]
+        fn test_write<S: Data<String>>() {
+            let mut string: ImString<S> = ImString::new();
+            string.write_str("Hello").unwrap();
+            string.write_char(',').unwrap();
+            string.write_char(' ').unwrap();
+            string.write_str("World").unwrap();
+            string.write_char('!').unwrap();
+            assert_eq!(string, "Hello, World!");
+
+        }
+
+        #[test]
+        fn test_add_assign<S: Data<String>>(string: ImString<S>) {
+            let mut std_string = string.as_str().to_string();
+            let mut string = string;
+            string += "hello";
+            std_string += "hello";
+            assert_eq!(string, std_string);
+        }
+
+        #[test]
+        fn test_add<S: Data<String>>(string: ImString<S>) {
+            let std_string = string.as_str().to_string();
+            let std_string = std_string + "hello";
+            let string = string + "hello";
+            assert_eq!(string, std_string);
+        }
+
+        #[test]
+        fn test_to_socket_addrs<S: Data<String>>(_string: ImString<S>) {
+            #[cfg(all(feature = "std", not(miri)))]
+            {
+                let addrs = _string.to_socket_addrs().map(|s| s.collect::<Vec<_>>());
+                let str_addrs = _string.as_str().to_socket_addrs().map(|s| s.collect::<Vec<_>>());
+                match addrs {
+                    Ok(addrs) => assert_eq!(addrs, str_addrs.unwrap()),
+                    Err(_err) => assert!(str_addrs.is_err()),
+                }
+            }
+        }
+
+        #[test]
+        fn test_from_iterator_char<S: Data<String>>() {
+            let input = ['h', 'e', 'l', 'l', 'o'];
+            let string: ImString<S> = input.into_iter().collect();
+            assert_eq!(string, "hello");
+        }
+
+        #[test]
+        fn test_from_iterator_char_ref<S: Data<String>>() {
+            let input = ['h', 'e', 'l', 'l', 'o'];
+            let string: ImString<S> = input.iter().collect();
+            assert_eq!(string, "hello");
+        }
+
+        #[test]
+        fn test_from_iterator_str<S: Data<String>>() {
+            let input = ["hello", "world", "!"];
+            let string: ImString<S> = input.into_iter().collect();
+            assert_eq!(string, "helloworld!");
+        }
+
+        #[test]
+        fn test_extend_char<S: Data<String>>() {
+            let input = ['h', 'e', 'l', 'l', 'o'];
+            let mut string: ImString<S> = ImString::new();
+            string.extend(input.into_iter());
+            assert_eq!(string, "hello");
+        }
+
+        #[test]
+        fn test_extend_char_ref<S: Data<String>>() {
+            let input = ['h', 'e', 'l', 'l', 'o'];
+            let mut string: ImString<S> = ImString::new();
+            string.extend(input.into_iter());
+            assert_eq!(string, "hello");
+        }
+
+        #[test]
+        fn test_extend_str<S: Data<String>>() {
+            let input = ["hello", "world", "!"];
+            let mut string: ImString<S> = ImString::new();
+            string.extend(input.into_iter());
+            assert_eq!(string, "helloworld!");
+        }
+
+        #[test]
+        fn test_from_utf8_lossy<S: Data<String>>() {
+            let string: ImString<S> = ImString::from_utf8_lossy(b"hello");
+            assert_eq!(string, "hello");
+        }
+
+        #[test]
+        fn test_from_utf8_unchecked<S: Data<String>>() {
+            let string: ImString<S> = unsafe {
+                ImString::from_utf8_unchecked(b"hello".to_vec())
+            };
+            assert_eq!(string, "hello");
+        }
+
+        #[test]
+        fn test_borrow<S: Data<String>>(string: ImString<S>) {
+            let s: &str = string.borrow();
+            assert_eq!(s, string.as_str());
+        }
+
+        #[test]
+        fn test_as_ref_str<S: Data<String>>(string: ImString<S>) {
+            let s: &str = string.as_ref();
+            assert_eq!(s, string.as_str());
+        }
+
+        #[test]
+        fn test_as_ref_bytes<S: Data<String>>(string: ImString<S>) {
+            let s: &[u8] = string.as_ref();
+            assert_eq!(s, string.as_bytes());
+        }
+
+        #[test]
+        fn test_as_ref_path<S: Data<String>>(_string: ImString<S>) {
+            #[cfg(feature = "std")]
+            {
+                let s: &Path = _string.as_ref();
+                assert_eq!(s, _string.as_str().as_ref() as &Path);
+            }
+        }
+
+        #[test]
+        fn test_as_ref_os_str<S: Data<String>>(_string: ImString<S>) {
+            #[cfg(feature = "std")]
+            {
+                let s: &OsStr = _string.as_ref();
+                assert_eq!(s, _string.as_str().as_ref() as &OsStr);
+            }
+        }
+
+        #[test]
+        fn test_deref_mut<S: Data<String>>(string: ImString<S>) {
+            let mut string = string;
+            let data = string.as_str().to_string();
+            let mutable: &mut str = string.deref_mut();
+            assert_eq!(&*mutable, &data);
+        }
+
+        #[test]
+        fn test_as_mut<S: Data<String>>(string: ImString<S>) {
+            let mut string = string;
+            let data = string.as_str().to_string();
+            let mutable: &mut str = string.as_mut();
+            assert_eq!(&*mutable, &data);
+        }
+
+        #[test]
+        fn test_borrow_mut<S: Data<String>>(string: ImString<S>) {
+            let mut string = string;
+            let data = string.as_str().to_string();
+            let mutable: &mut str = string.borrow_mut();
+            assert_eq!(&*mutable, &data);
+        }
+
+        #[test]
+        fn test_partial_eq<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string, string.as_str());
+            assert_eq!(string, string.to_string());
+            assert_eq!(string, string);
+        }
+
+        #[test]
+        fn test_partial_eq_bytes<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string, string.as_bytes());
+            assert_eq!(&string, string.as_bytes());
+            assert!(string == *string.as_bytes());
+            let mut other = string.as_bytes().to_vec();
+            other.push(b'!');
+            assert_ne!(string, other.as_slice());
+        }
+
+        #[test]
+        fn test_partial_eq_cow<S: Data<String>>(string: ImString<S>) {
+            let borrowed: Cow<str> = Cow::Borrowed(string.as_str());
+            let owned: Cow<str> = Cow::Owned(string.to_string());
+            assert_eq!(string, borrowed);
+            assert_eq!(string, owned);
+            assert_eq!(borrowed, string);
+            assert_eq!(owned, string);
+            let other: Cow<str> = Cow::Owned(format!("{string}!"));
+            assert_ne!(string, other);
+            assert_ne!(other, string);
+        }
+
+        #[test]
+        fn test_partial_eq_bytes_content<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("GET / HTTP/1.1").slice(6..);
+            assert_eq!(string, &b"HTTP/1.1"[..]);
+            assert_ne!(string, &b"HTTP/1.0"[..]);
+            assert_ne!(string, &b"HTTP/1.1\r\n"[..]);
+            assert_ne!(string, &b""[..]);
+        }
+
+        #[test]
+        fn test_partial_ord<S: Data<String>>(string: ImString<S>) {
+            let other = ImString::from("test");
+            assert_eq!(string.as_str().partial_cmp(other.as_str()), string.partial_cmp(&other));
+            assert_eq!(other.as_str().partial_cmp(string.as_str()), other.partial_cmp(&string));
+        }
+
+        #[test]
+        fn test_ord<S: Data<String>>(string: ImString<S>) {
+            let other = ImString::from("test");
+            assert_eq!(string.as_str().cmp(other.as_str()), string.cmp(&other));
+            assert_eq!(other.as_str().cmp(string.as_str()), other.cmp(&string));
+        }
+
+        #[test]
+        fn test_from<S: Data<String>>(string: ImString<S>) {
+            let std_string: String = string.clone().into();
+            assert_eq!(string, std_string);
+        }
+
+        #[test]
+        fn test_raw_offset<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.offset, string.raw_offset());
+        }
+
+        #[test]
+        fn test_raw_string<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.string.get(), string.raw_string().get());
+        }
+
+        #[test]
+        fn into_std_string<S: Data<String>>(string: ImString<S>) {
+            let std_clone = string.as_str().to_string();
+            let std_string = string.into_std_string();
+            assert_eq!(std_clone, std_string);
+        }
+
+        #[test]
+        fn test_truncate<S: Data<String>>(string: ImString<S>) {
+            let mut clone = string.as_str().to_string();
+            let mut string = string;
 
-    fn test_strings<S: Data<String>>() -> Vec<ImString<S>> {
-        let long = ImString::from("long string here");
-        let world = ImString::from("world");
-        let some = ImString::from("some");
-        let multiline = ImString::from("some\nmulti\nline\nstring\nthat\nis\nlong");
-        let large: ImString<S> = (0..100).map(|_| "hello\n").collect();
-        vec![
-            ImString::new(),
-            ImString::default(),
-            ImString::from(""),
-            ImString::from("a"),
-            ImString::from("ü"),
-            ImString::from("hello"),
-            ImString::from("0.0.0.0:800"),
-            ImString::from("localhost:1234"),
-            ImString::from("0.0.0.0:1234"),
-            large.slice(0..6),
-            large.slice(6..12),
-            large.slice(..),
-            long.clone(),
-            long.slice(4..10),
-            long.slice(0..4),
-            long.slice(4..4),
-            long.slice(5..),
-            long.slice(..),
-            world.clone(),
-            world.clone(),
-            some.slice(4..),
-            some,
-            multiline.slice(5..15),
-            multiline,
-            ImString::from("\u{e4}\u{fc}\u{f6}\u{f8}\u{3a9}"),
-            ImString::from("\u{1f600}\u{1f603}\u{1f604}"),
-            ImString::from("o\u{308}u\u{308}a\u{308}"),
-        ]
-    }
+            for length in (0..string.len()).rev() {
+                if string.is_char_boundary(length) {
+                    string.truncate(length);
+                    clone.truncate(length);
+                    assert_eq!(string, clone);
+                }
+            }
+        }
 
-    macro_rules! tests {
-        () => {};
-        (#[test] fn $name:ident <S: Data<String>>() $body:tt $($rest:tt)*) => {
-            #[test]
-            fn $name() {
-                fn $name <S: Data<String>>() $body
-                $name::<Threadsafe>();
-                $name::<Local>();
-                $name::<Cloned<String>>();
-                $name::<Box<String>>();
+        #[test]
+        fn test_str_ref<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string, string.str_ref(string.as_str()));
+        }
+
+        #[test]
+        fn test_try_str_ref<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string, string.try_str_ref(string.as_str()).unwrap());
+            assert_eq!(string.try_str_ref("test"), None);
+        }
+
+        #[test]
+        fn test_slice_ref<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string, string.slice_ref(string.as_bytes()));
+        }
+
+        #[test]
+        fn test_try_slice_ref<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string, string.try_slice_ref(string.as_bytes()).unwrap());
+            assert_eq!(string.try_slice_ref(b"test"), None);
+        }
+
+        #[test]
+        fn test_from_into<S: Data<String>>() {
+            let string: ImString<S> = ImString::from_into("hello");
+            assert_eq!(string, "hello");
+            let string: ImString<S> = ImString::from_into(String::from("hello"));
+            assert_eq!(string, "hello");
+            let string: ImString<S> = ImString::from_into(Cow::Borrowed("hello"));
+            assert_eq!(string, "hello");
+            let string: ImString<S> = ImString::from_into(Cow::<str>::Owned("hello".into()));
+            assert_eq!(string, "hello");
+            let string: ImString<S> = ImString::from_into('h');
+            assert_eq!(string, "h");
+            let string: ImString<S> = ImString::from_into(Box::<str>::from("hello"));
+            assert_eq!(string, "hello");
+        }
+
+        #[test]
+        fn test_parse_trimmed<S: Data<String>>() {
+            let string: ImString<S> = ImString::from(" 14 ");
+            assert_eq!(string.parse_trimmed::<i64>(), Some(14));
+            assert_eq!(string.parse_trimmed::<f64>(), Some(14.0));
+            let string: ImString<S> = ImString::from(" true ");
+            assert_eq!(string.parse_trimmed::<bool>(), Some(true));
+            let string: ImString<S> = ImString::from("\tfalse\n");
+            assert_eq!(string.parse_trimmed::<bool>(), Some(false));
+            let string: ImString<S> = ImString::from(" 1 4 ");
+            assert_eq!(string.parse_trimmed::<i64>(), None);
+        }
+
+        #[test]
+        fn test_to_ascii_safe<S: Data<String>>(string: ImString<S>) {
+            let safe = string.to_ascii_safe('?');
+            assert!(safe.is_ascii());
+            assert_eq!(safe.chars().count(), string.chars().count());
+            if string.is_ascii() {
+                assert_eq!(safe, string);
+                assert_eq!(safe.offset, string.offset);
             }
-            tests!{$($rest)*}
-        };
-        (#[test] fn $name:ident <S: Data<String>>($string:ident: ImString<S>) $body:tt $($rest:tt)*) => {
-            #[test]
-            fn $name() {
-                fn $name <S: Data<String>>() {
-                    fn $name <S: Data<String>>($string: ImString<S>) $body
-                    for string in test_strings::<S>().into_iter() {
-                        $name(string);
-                    }
+        }
+
+        #[test]
+        fn test_to_ascii_safe_mixed<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("öl, über, straße");
+            assert_eq!(string.to_ascii_safe('_'), "_l, _ber, stra_e");
+            let string: ImString<S> = ImString::from("\u{1f600} smile");
+            assert_eq!(string.to_ascii_safe('?'), "? smile");
+        }
+
+        #[test]
+        fn test_char_boundaries<S: Data<String>>(string: ImString<S>) {
+            let boundaries: Vec<usize> = string.char_boundaries().collect();
+            let expected: Vec<usize> = (0..=string.len())
+                .filter(|index| string.is_char_boundary(*index))
+                .collect();
+            assert_eq!(boundaries, expected);
+        }
+
+        #[test]
+        fn test_wrap<S: Data<String>>(string: ImString<S>) {
+            for width in [1, 3, 10] {
+                for line in string.wrap(width) {
+                    assert!(line.chars().count() <= width);
+                    assert!(string.contains(line.as_str()));
                 }
-                $name::<Threadsafe>();
-                $name::<Local>();
-                $name::<Cloned<String>>();
-                $name::<Box<String>>();
             }
-            tests!{$($rest)*}
         }
+
+        #[test]
+        fn test_wrap_words<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("the quick brown fox jumps over the lazy dog");
+            assert_eq!(string.wrap(10), ["the quick", "brown fox", "jumps over", "the lazy", "dog"]);
+            assert_eq!(string.wrap(9), ["the quick", "brown fox", "jumps", "over the", "lazy dog"]);
+            assert_eq!(string.wrap(100), [string.as_str()]);
+        }
+
+        #[test]
+        fn test_wrap_leading_whitespace<S: Data<String>>() {
+            let string: ImString<S> = ImString::from(" hello world");
+            assert_eq!(string.wrap(5), ["hello", "world"]);
+            let string: ImString<S> = ImString::from("  hello world");
+            assert_eq!(string.wrap(5), ["hello", "world"]);
+            let string: ImString<S> = ImString::from("first\n   indented line");
+            assert_eq!(string.wrap(8), ["first", "indented", "line"]);
+        }
+
+        #[test]
+        fn test_wrap_long_word<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("a extraordinarily long wörd");
+            assert_eq!(string.wrap(5), ["a", "extra", "ordin", "arily", "long", "wörd"]);
+            let string: ImString<S> = ImString::from("üüüüü");
+            assert_eq!(string.wrap(2), ["üü", "üü", "ü"]);
+        }
+
+        #[test]
+        fn test_wrap_newlines<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("first line\n\nsecond line here\r\nthird");
+            assert_eq!(string.wrap(11), ["first line", "", "second line", "here", "third"]);
+        }
+
+        #[test]
+        fn test_line_ranges<S: Data<String>>(string: ImString<S>) {
+            let lines: Vec<_> = string.line_ranges().collect();
+            assert_eq!(lines.len(), string.as_str().lines().count());
+            let mut end = 0;
+            for ((line, range), expected) in lines.iter().zip(string.as_str().lines()) {
+                assert_eq!(line, expected);
+                assert_eq!(&string[range.clone()], expected);
+                if range.start > 0 {
+                    let terminator = &string[end..range.start];
+                    assert!(terminator == "\n" || terminator == "\r\n");
+                }
+                end = range.end;
+            }
+        }
+
+        #[test]
+        fn test_line_ranges_crlf<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("one\r\ntwo\n\r\nthree\r\n");
+            let lines: Vec<_> = string.line_ranges().collect();
+            assert_eq!(lines, [
+                (ImString::from("one"), 0..3),
+                (ImString::from("two"), 5..8),
+                (ImString::from(""), 9..9),
+                (ImString::from("three"), 11..16),
+            ]);
+
+            let string = string.slice(5..);
+            let lines: Vec<_> = string.line_ranges().collect();
+            assert_eq!(lines[0], (ImString::from("two"), 0..3));
+            assert_eq!(lines[0].0.offset, 5..8);
+        }
+
+        #[test]
+        fn test_common_prefix_suffix<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.common_prefix(&string), string);
+            assert_eq!(string.common_suffix(&string), string);
+            assert_eq!(string.common_prefix(&ImString::new()), "");
+            assert_eq!(string.common_suffix(&ImString::new()), "");
+            let other = ImString::from("\u{1f4a9}");
+            assert_eq!(string.common_prefix(&other), "");
+            assert_eq!(string.common_suffix(&other), "");
+        }
+
+        #[test]
+        fn test_common_prefix_suffix_multibyte<S: Data<String>>() {
+            // 'ä' is C3 A4 and 'ã' is C3 A3, so the raw bytes share a prefix
+            let string: ImString<S> = ImString::from("abä");
+            let other: ImString<S> = ImString::from("abã");
+            assert_eq!(string.common_prefix(&other), "ab");
+
+            // 'ä' is C3 A4 and 'Ĥ' is C4 A4, so the raw bytes share a suffix
+            let string: ImString<S> = ImString::from("äyz");
+            let other: ImString<S> = ImString::from("Ĥyz");
+            assert_eq!(string.common_suffix(&other), "yz");
+            let string: ImString<S> = ImString::from("xä");
+            let other: ImString<S> = ImString::from("xĤ");
+            assert_eq!(string.common_suffix(&other), "");
+            assert_eq!(string.common_prefix(&other), "x");
+        }
+
+        #[test]
+        fn test_replace_char<S: Data<String>>(string: ImString<S>) {
+            let from = string.chars().next().unwrap_or('a');
+            for to in ['a', 'ä', '€', '\u{1f4a9}'] {
+                let mut buffer = [0; 4];
+                assert_eq!(
+                    string.replace_char(from, to),
+                    string.replace(from, to.encode_utf8(&mut buffer))
+                );
+            }
+        }
+
+        #[test]
+        fn test_replace_char_width<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("banana").slice(1..);
+            assert_eq!(string.replace_char('a', 'o'), "onono");
+            assert_eq!(string.replace_char('a', '€'), "€n€n€");
+            assert_eq!(string.replace_char('n', '\u{1f4a9}'), "a\u{1f4a9}a\u{1f4a9}a");
+            let string: ImString<S> = ImString::from("€uro");
+            assert_eq!(string.replace_char('€', 'E'), "Euro");
+            assert_eq!(string.replace_char('€', '₤'), "₤uro");
+        }
+
+        #[test]
+        fn test_escape<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.escape_default(), string.as_str().escape_default().to_string());
+            assert_eq!(string.escape_debug(), string.as_str().escape_debug().to_string());
+        }
+
+        #[test]
+        fn test_escape_control<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("a\nb\tc\u{7}dü");
+            assert_eq!(string.escape_default(), string.as_str().escape_default().to_string());
+            assert_eq!(string.escape_default(), "a\\nb\\tc\\u{7}d\\u{fc}");
+            assert_eq!(string.escape_debug(), string.as_str().escape_debug().to_string());
+            assert_eq!(string.escape_debug(), "a\\nb\\tc\\u{7}dü");
+        }
+
+        #[test]
+        fn test_from_code_points_lossy_valid<S: Data<String>>(string: ImString<S>) {
+            let points = string.chars().map(u32::from);
+            let result: ImString<S> = ImString::from_code_points_lossy(points);
+            assert_eq!(result, string);
+        }
+
+        #[test]
+        fn test_from_code_points_lossy_invalid<S: Data<String>>() {
+            let result: ImString<S> = ImString::from_code_points_lossy([]);
+            assert_eq!(result, "");
+            let result: ImString<S> =
+                ImString::from_code_points_lossy([0x61, 0xD800, 0xDFFF, 0x62, 0x110000, u32::MAX]);
+            assert_eq!(result, "a\u{FFFD}\u{FFFD}b\u{FFFD}\u{FFFD}");
+            let result: ImString<S> = ImString::from_code_points_lossy([0xD7FF, 0xE000, 0x10FFFF]);
+            assert_eq!(result, "\u{D7FF}\u{E000}\u{10FFFF}");
+        }
+
+        #[test]
+        fn test_truncate_floor<S: Data<String>>(string: ImString<S>) {
+            for length in 0..=string.len() + 1 {
+                let mut truncated = string.clone();
+                truncated.truncate_floor(length);
+                assert!(truncated.len() <= length);
+                assert!(string.starts_with(truncated.as_str()));
+                assert!(truncated.len() + 4 > length || truncated.len() == string.len());
+            }
+        }
+
+        #[test]
+        fn test_truncate_floor_mid_char<S: Data<String>>() {
+            // '€' is three bytes long, starting at byte 1
+            let string: ImString<S> = ImString::from("a€b");
+            let mut truncated = string.clone();
+            truncated.truncate_floor(4);
+            assert_eq!(truncated, "a€");
+            let mut truncated = string.clone();
+            truncated.truncate_floor(3);
+            assert_eq!(truncated, "a");
+            let mut truncated = string.clone();
+            truncated.truncate_floor(1);
+            assert_eq!(truncated, "a");
+            let mut truncated = string.clone().slice(1..);
+            truncated.truncate_floor(2);
+            assert_eq!(truncated, "");
+            let mut truncated = string;
+            truncated.truncate_floor(10);
+            assert_eq!(truncated, "a€b");
+        }
+
+        #[test]
+        fn test_as_cow<S: Data<String>>(string: ImString<S>) {
+            match string.as_cow() {
+                Cow::Borrowed(borrowed) => {
+                    assert_eq!(borrowed, string.as_str());
+                    assert_eq!(borrowed.as_ptr(), string.as_ptr());
+                }
+                Cow::Owned(_) => panic!("as_cow() should borrow"),
+            }
+        }
+
+        #[test]
+        fn test_into_cow<S: Data<String>>(string: ImString<S>) {
+            let expected = string.as_str().to_string();
+            match string.into_cow() {
+                Cow::Owned(owned) => assert_eq!(owned, expected),
+                Cow::Borrowed(_) => panic!("into_cow() should own"),
+            }
+        }
+
+        #[test]
+        fn test_split_parse<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("1,2,3");
+            let numbers: Vec<Result<u8, _>> = string.split_parse(",").collect();
+            assert_eq!(numbers, [Ok(1), Ok(2), Ok(3)]);
+
+            let string: ImString<S> = ImString::from("[1, 2, 3]").slice(1..8);
+            let numbers: Result<Vec<u8>, _> = string.split_parse(", ").collect();
+            assert_eq!(numbers, Ok(vec![1, 2, 3]));
+
+            let string: ImString<S> = ImString::from("1,x,3");
+            let mut numbers = string.split_parse::<u8>(",");
+            assert_eq!(numbers.next(), Some(Ok(1)));
+            assert!(numbers.next().unwrap().is_err());
+            assert_eq!(numbers.next(), Some(Ok(3)));
+            assert_eq!(numbers.next(), None);
+            let numbers: Result<Vec<u8>, _> = string.split_parse(",").collect();
+            assert!(numbers.is_err());
+
+            let string: ImString<S> = ImString::new();
+            let numbers: Vec<Result<u8, _>> = string.split_parse(",").collect();
+            assert_eq!(numbers.len(), 1);
+            assert!(numbers[0].is_err());
+        }
+
+        #[test]
+        fn test_trim_with_offsets<S: Data<String>>(string: ImString<S>) {
+            let (trimmed, start, end) = string.trim_with_offsets();
+            assert_eq!(trimmed, string.trim());
+            assert_eq!(start + trimmed.len() + end, string.len());
+            assert_eq!(trimmed, &string[start..string.len() - end]);
+        }
+
+        #[test]
+        fn test_trim_with_offsets_sides<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("  leading");
+            assert_eq!(string.trim_with_offsets(), (ImString::from("leading"), 2, 0));
+            let string: ImString<S> = ImString::from("trailing\t\n");
+            assert_eq!(string.trim_with_offsets(), (ImString::from("trailing"), 0, 2));
+            let string: ImString<S> = ImString::from(" both ");
+            assert_eq!(string.trim_with_offsets(), (ImString::from("both"), 1, 1));
+            // U+3000 (ideographic space) is three bytes, U+00A0 (no-break space) is two bytes
+            let string: ImString<S> = ImString::from("\u{3000}wide\u{a0}");
+            assert_eq!(string.trim_with_offsets(), (ImString::from("wide"), 3, 2));
+            let string: ImString<S> = ImString::from(" \u{3000} ");
+            assert_eq!(string.trim_with_offsets(), (ImString::new(), 5, 0));
+            let string: ImString<S> = ImString::new();
+            assert_eq!(string.trim_with_offsets(), (ImString::new(), 0, 0));
+        }
+
+        #[test]
+        fn test_search_line<S: Data<String>>() {
+            let words = ["", "apple", "banana", "cherry", "date", "elderberry", "fig", "ü"];
+            for count in 0..=words.len() {
+                let lines = &words[..count];
+                let string: ImString<S> = ImString::from(lines.join("\n"));
+                for word in words.iter().chain(&["appl", "apples", "zzz", "\n"]) {
+                    let expected = string.as_str().lines().find(|line| line == word);
+                    assert_eq!(string.search_line(word), expected.map(ImString::from));
+                }
+            }
+        }
+
+        #[test]
+        fn test_search_line_terminators<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("a\r\nb\nc\r\n");
+            assert_eq!(string.search_line("a").unwrap(), "a");
+            assert_eq!(string.search_line("b").unwrap(), "b");
+            assert_eq!(string.search_line("c").unwrap(), "c");
+            assert_eq!(string.search_line("c\r"), None);
+            assert_eq!(string.search_line(""), None);
+            let string: ImString<S> = ImString::from("x\nalpha\nbeta\ngamma\ny").slice(2..18);
+            assert_eq!(string.search_line("beta").unwrap(), "beta");
+            assert_eq!(string.search_line("gamma").unwrap(), "gamma");
+            assert_eq!(string.search_line("x"), None);
+
+            // a final `\r` without `\n` is part of the line, as with lines()
+            let string: ImString<S> = ImString::from("a\r\nb\r");
+            assert_eq!(string.as_str().lines().collect::<Vec<_>>(), ["a", "b\r"]);
+            assert_eq!(string.search_line("a").unwrap(), "a");
+            assert_eq!(string.search_line("b\r").unwrap(), "b\r");
+            assert_eq!(string.search_line("b"), None);
+        }
+
+        #[test]
+        fn test_into_string<S: Data<String>>(string: ImString<S>) {
+            let expected = string.as_str().to_string();
+            assert_eq!(string.clone().into_string(), expected);
+            assert_eq!(string.clone().into_std_string(), expected);
+            assert_eq!(string.clone().into_string(), string.into_std_string());
+        }
+
+        #[test]
+        fn test_into_std_string_slice<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("hello, world");
+            let shared = string.slice(7..);
+            assert_eq!(shared.clone().into_std_string(), "world");
+            assert_eq!(string.slice(2..5).into_std_string(), "llo");
+            drop(shared);
+            assert_eq!(string.slice(7..).into_std_string(), "world");
+            assert_eq!(string, "hello, world");
+
+            // unique backing with a nonzero start, which is drained instead of copied
+            let unique = ImString::<S>::from("hello, wörld!").slice(7..13);
+            let pointer = unique.string.get().as_ptr();
+            let unique = unique.into_std_string();
+            assert_eq!(unique, "wörld");
+            assert_eq!(unique.as_ptr(), pointer);
+        }
+
+        #[test]
+        fn test_substring<S: Data<String>>(string: ImString<S>) {
+            let chars: Vec<char> = string.chars().collect();
+            // only test around the edges, testing every index is too slow for long strings
+            let count = chars.len();
+            let indices = [0, 1, 2, count / 2, count.saturating_sub(1), count, count + 1];
+            for start in indices {
+                for len in indices {
+                    let expected = chars
+                        .get(start..start + len)
+                        .map(|chars| chars.iter().collect::<String>());
+                    let substring = string.substring(start, len);
+                    assert_eq!(substring.as_deref(), expected.as_deref());
+                }
+            }
+        }
+
+        #[test]
+        fn test_substring_multibyte<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("a€\u{1f4a9}b");
+            assert_eq!(string.substring(0, 4).unwrap(), "a€\u{1f4a9}b");
+            assert_eq!(string.substring(1, 2).unwrap(), "€\u{1f4a9}");
+            assert_eq!(string.substring(2, 1).unwrap(), "\u{1f4a9}");
+            assert_eq!(string.substring(4, 0).unwrap(), "");
+            assert_eq!(string.substring(3, 2), None);
+            assert_eq!(string.substring(5, 0), None);
+            let string = string.slice(1..);
+            assert_eq!(string.substring(1, 2).unwrap(), "\u{1f4a9}b");
+        }
+
+        #[test]
+        fn test_eq_ignoring<S: Data<String>>(string: ImString<S>) {
+            assert!(string.eq_ignoring(string.as_str(), &[]));
+            assert!(string.eq_ignoring(string.as_str(), &['a', 'ü']));
+            let filtered: String = string.chars().filter(|c| *c != 'a').collect();
+            assert!(string.eq_ignoring(&filtered, &['a']));
+        }
+
+        #[test]
+        fn test_eq_ignoring_separators<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("foo_bar");
+            assert!(string.eq_ignoring("foobar", &['_']));
+            assert!(!string.eq_ignoring("foobar", &[]));
+            assert!(!string.eq_ignoring("foo-bar", &['_']));
+            assert!(string.eq_ignoring("foo-bar", &['_', '-']));
+            assert!(string.eq_ignoring("_f_o_o_b_a_r_", &['_']));
+            assert!(!string.eq_ignoring("foobaz", &['_']));
+            assert!(!string.eq_ignoring("foobarx", &['_']));
+            let string: ImString<S> = ImString::from("___");
+            assert!(string.eq_ignoring("", &['_']));
+        }
+
     }
 
     tests! {
         #[test]
-        fn test_new<S: Data<String>>() {
-            let string: ImString<S> = ImString::new();
-            assert_eq!(string.string.get().len(), 0);
-            assert_eq!(string.offset, 0..0);
+        fn test_take_while_char<S: Data<String>>(string: ImString<S>) {
+            let (head, tail) = string.take_while_char(|c| c != 'l');
+            assert_eq!(format!("{head}{tail}"), string.as_str());
+            assert!(!head.contains('l'));
+            assert!(tail.is_empty() || tail.starts_with('l'));
+        }
+
+        #[test]
+        fn test_take_while_char_tokenize<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("abc123");
+            let (word, rest) = string.take_while_char(char::is_alphabetic);
+            assert_eq!((word.as_str(), rest.as_str()), ("abc", "123"));
+            let (number, rest) = rest.take_while_char(|c| c.is_ascii_digit());
+            assert_eq!((number.as_str(), rest.as_str()), ("123", ""));
+            let (word, rest) = number.take_while_char(char::is_alphabetic);
+            assert_eq!((word.as_str(), rest.as_str()), ("", "123"));
+            let string: ImString<S> = ImString::from("größe=1");
+            let (word, rest) = string.take_while_char(char::is_alphabetic);
+            assert_eq!((word.as_str(), rest.as_str()), ("größe", "=1"));
+        }
+
+        #[test]
+        fn test_normalize_newlines<S: Data<String>>(string: ImString<S>) {
+            let expected = string.as_str().replace("\r\n", "\n").replace('\r', "\n");
+            assert_eq!(string.normalize_newlines(), expected);
+        }
+
+        #[test]
+        fn test_normalize_newlines_endings<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("a\r\nb\r\n");
+            assert_eq!(string.normalize_newlines(), "a\nb\n");
+            let string: ImString<S> = ImString::from("a\rb\r");
+            assert_eq!(string.normalize_newlines(), "a\nb\n");
+            let string: ImString<S> = ImString::from("\r\r\n\n\n\r");
+            assert_eq!(string.normalize_newlines(), "\n\n\n\n\n");
+            let string: ImString<S> = ImString::from("ü\r\nß");
+            assert_eq!(string.normalize_newlines(), "ü\nß");
+            let string: ImString<S> = ImString::from("a\nb\n");
+            assert_eq!(string.normalize_newlines(), "a\nb\n");
+        }
+
+        #[test]
+        fn test_strip_bom<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.strip_bom(), string);
+            let with_bom: ImString<S> = ImString::from(format!("\u{feff}{string}"));
+            assert_eq!(with_bom.strip_bom(), string);
+        }
+
+        #[test]
+        fn test_strip_bom_once<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("\u{feff}\u{feff}text\u{feff}");
+            assert_eq!(string.strip_bom(), "\u{feff}text\u{feff}");
+            let string: ImString<S> = ImString::from("text\u{feff}");
+            assert_eq!(string.strip_bom(), "text\u{feff}");
+            let string: ImString<S> = ImString::from("\u{feff}");
+            assert_eq!(string.strip_bom(), "");
+        }
+
+        #[test]
+        fn test_leak<S: Data<String>>(string: ImString<S>) {
+            let expected = string.as_str().to_string();
+            let leaked: &'static str = string.leak();
+            assert_eq!(leaked, expected);
+        }
+
+        #[test]
+        fn test_split_first_last_char<S: Data<String>>(string: ImString<S>) {
+            let mut chars = string.as_str().chars();
+            match (chars.next(), string.split_first_char()) {
+                (Some(c), Some((first, rest))) => {
+                    assert_eq!(first, c);
+                    assert_eq!(rest, chars.as_str());
+                }
+                (None, None) => assert!(string.is_empty()),
+                _ => panic!("split_first_char() returned wrong result"),
+            }
+            let mut chars = string.as_str().chars();
+            match (chars.next_back(), string.split_last_char()) {
+                (Some(c), Some((front, last))) => {
+                    assert_eq!(last, c);
+                    assert_eq!(front, chars.as_str());
+                }
+                (None, None) => assert!(string.is_empty()),
+                _ => panic!("split_last_char() returned wrong result"),
+            }
         }
 
         #[test]
-        fn test_default<S: Data<String>>() {
+        fn test_split_first_last_char_multibyte<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("a€\u{1f4a9}");
+            assert_eq!(string.split_first_char(), Some(('a', ImString::from("€\u{1f4a9}"))));
+            assert_eq!(string.split_last_char(), Some((ImString::from("a€"), '\u{1f4a9}')));
+            let string: ImString<S> = string.slice(1..4);
+            assert_eq!(string.split_first_char(), Some(('€', ImString::new())));
+            assert_eq!(string.split_last_char(), Some((ImString::new(), '€')));
             let string: ImString<S> = ImString::new();
-            assert_eq!(string.string.get().len(), 0);
-            assert_eq!(string.offset, 0..0);
+            assert_eq!(string.split_first_char(), None);
+            assert_eq!(string.split_last_char(), None);
         }
 
         #[test]
-        fn test_with_capacity<S: Data<String>>() {
-            for capacity in [10, 100, 256] {
-                let string: ImString<S> = ImString::with_capacity(capacity);
-                assert!(string.capacity() >= capacity);
-                assert_eq!(string.string.get().len(), 0);
-                assert_eq!(string.offset, 0..0);
-            }
+        fn test_replace_all<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.replace_all(&[]), string);
+            assert_eq!(string.replace_all(&[("", "x")]), string);
+            assert_eq!(string.replace_all(&[("l", "L")]), string.replace('l', "L"));
+            assert_eq!(
+                string.replace_all(&[("l", "o"), ("o", "l")]),
+                string
+                    .chars()
+                    .map(|c| match c {
+                        'l' => 'o',
+                        'o' => 'l',
+                        c => c,
+                    })
+                    .collect::<String>()
+            );
         }
 
         #[test]
-        fn test_offset<S: Data<String>>(string: ImString<S>) {
-            assert!(string.offset.start <= string.string.get().len());
-            assert!(string.offset.start <= string.offset.end);
-            assert!(string.offset.end <= string.string.get().len());
+        fn test_replace_all_overlapping<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("a <= b < c");
+            assert_eq!(string.replace_all(&[("<=", "≤"), ("<", "&lt;")]), "a ≤ b &lt; c");
+            assert_eq!(string.replace_all(&[("<", "&lt;"), ("<=", "≤")]), "a &lt;= b &lt; c");
+            let string: ImString<S> = ImString::from("aaa");
+            assert_eq!(string.replace_all(&[("aa", "b")]), "ba");
+            assert_eq!(string.replace_all(&[("a", "aa")]), "aaaaaa");
+            let string: ImString<S> = ImString::from("Grüße");
+            assert_eq!(string.replace_all(&[("ü", "ue"), ("ß", "ss")]), "Gruesse");
+            let string: ImString<S> = ImString::from("&lt;&amp;");
+            assert_eq!(string.replace_all(&[("&lt;", "<"), ("&amp;", "&")]), "<&");
         }
 
         #[test]
-        fn test_as_str<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(string.as_str(), &string.string.get()[string.offset.clone()]);
-            assert_eq!(string.as_str().len(), string.len());
+        fn test_from_cow<S: Data<String>>(string: ImString<S>) {
+            let borrowed: ImString<S> = ImString::from(Cow::Borrowed(string.as_str()));
+            assert_eq!(borrowed, string);
+            let owned: ImString<S> = ImString::from(Cow::<str>::Owned(string.to_string()));
+            assert_eq!(owned, string);
         }
 
         #[test]
-        fn test_as_mut_str<S: Data<String>>(string: ImString<S>) {
-            // ascii uppercase copy of string
-            let string_uppercase = string.as_str().to_ascii_uppercase();
-
-            // uppercase in-place
-            let mut string = string;
-            let string_slice = string.as_mut_str();
-            string_slice.make_ascii_uppercase();
-
-            // make sure both versions are identical
-            assert_eq!(string_uppercase, &*string_slice);
-            drop(string_slice);
-            assert_eq!(string, string_uppercase);
+        fn test_line_count<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.line_count(), string.as_str().lines().count());
         }
 
         #[test]
-        fn test_as_bytes<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(string.as_bytes(), &string.string.get().as_bytes()[string.offset.clone()]);
-            assert_eq!(string.as_bytes().len(), string.len());
+        fn test_line_count_terminators<S: Data<String>>() {
+            for input in ["", "\n", "\n\n", "a", "a\n", "a\nb", "a\nb\n", "a\r\nb\r\n", "\na"] {
+                let string: ImString<S> = ImString::from(input);
+                assert_eq!(string.line_count(), input.lines().count(), "{input:?}");
+            }
+            let string: ImString<S> = ImString::from("a\nb\nc").slice(2..4);
+            assert_eq!(string.line_count(), 1);
         }
 
         #[test]
-        fn test_len<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(string.len(), string.offset.len());
-            assert_eq!(string.len(), string.as_str().len());
-            assert_eq!(string.len(), string.as_bytes().len());
+        fn test_len_bytes_chars<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.len_bytes(), string.len());
+            assert_eq!(string.len_chars(), string.as_str().chars().count());
         }
 
         #[test]
-        fn test_clear<S: Data<String>>(string: ImString<S>) {
-            let mut string = string;
-            string.clear();
-            assert_eq!(string.as_str(), "");
-            assert_eq!(string.len(), 0);
+        fn test_len_chars_multibyte<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("a€\u{1f4a9}ü");
+            assert_eq!(string.len_bytes(), 10);
+            assert_eq!(string.len_bytes(), string.len());
+            assert_eq!(string.len_chars(), 4);
+            assert_eq!(string.slice(1..).len_chars(), 3);
         }
 
         #[test]
-        fn test_debug<S: Data<String>>(string: ImString<S>) {
-            let debug_string = format!("{string:?}");
-            let debug_str = format!("{:?}", string.as_str());
-            assert_eq!(debug_string, debug_str);
+        fn test_find_slice<S: Data<String>>(string: ImString<S>) {
+            let copy = string.as_str().to_string();
+            assert_eq!(string.find_slice(&copy).unwrap(), string);
+            assert_eq!(string.find_slice("").unwrap(), "");
+            assert_eq!(string.find_slice("\u{0}not found"), None);
         }
 
         #[test]
-        fn test_deref<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(string.deref(), string.as_str());
+        fn test_find_slice_position<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("one two one").slice(1..);
+            let found = string.find_slice(&String::from("one")).unwrap();
+            assert_eq!(found, "one");
+            assert_eq!(found.offset, 8..11);
+            assert_eq!(string.find_slice("ne t").unwrap().offset, 1..5);
+            assert_eq!(string.find_slice("one two"), None);
+            let string: ImString<S> = ImString::from("grüße");
+            assert_eq!(string.find_slice("üß").unwrap().offset, 2..6);
         }
+    }
 
+    tests! {
         #[test]
-        fn test_clone<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(string, string.clone());
+        fn test_rotate_chars<S: Data<String>>(string: ImString<S>) {
+            let chars: Vec<char> = string.chars().collect();
+            assert_eq!(string.rotate_left_chars(0), string);
+            assert_eq!(string.rotate_right_chars(0), string);
+            assert_eq!(string.rotate_left_chars(chars.len()), string);
+            assert_eq!(string.rotate_right_chars(chars.len()), string);
+            if !chars.is_empty() {
+                let mut rotated = chars.clone();
+                rotated.rotate_left(1);
+                assert_eq!(string.rotate_left_chars(1), rotated.iter().collect::<String>());
+                let mut rotated = chars.clone();
+                rotated.rotate_right(1);
+                assert_eq!(string.rotate_right_chars(1), rotated.iter().collect::<String>());
+            }
         }
 
         #[test]
-        fn test_display<S: Data<String>>(string: ImString<S>) {
-            let display_string = format!("{string}");
-            let display_str = format!("{}", string.as_str());
-            assert_eq!(display_string, display_str);
+        fn test_rotate_chars_multibyte<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("a€\u{1f4a9}ü");
+            assert_eq!(string.rotate_left_chars(1), "€\u{1f4a9}üa");
+            assert_eq!(string.rotate_left_chars(3), "üa€\u{1f4a9}");
+            assert_eq!(string.rotate_right_chars(1), "üa€\u{1f4a9}");
+            assert_eq!(string.rotate_right_chars(3), "€\u{1f4a9}üa");
+            assert_eq!(string.rotate_left_chars(4), "a€\u{1f4a9}ü");
+            assert_eq!(string.rotate_right_chars(9), "üa€\u{1f4a9}");
+            let string: ImString<S> = ImString::new();
+            assert_eq!(string.rotate_left_chars(3), "");
+            assert_eq!(string.rotate_right_chars(3), "");
         }
 
         #[test]
-        fn test_insert_start<S: Data<String>>(string: ImString<S>) {
-            let mut string = string;
-            let length = string.len();
-            string.insert(0, 'h');
-            assert_eq!(string.len(), length + 1);
-            assert_eq!(string.chars().nth(0), Some('h'));
+        fn test_char_at<S: Data<String>>(string: ImString<S>) {
+            for index in 0..=string.len() + 1 {
+                let expected = string.as_str().get(index..).and_then(|s| s.chars().next());
+                assert_eq!(string.char_at(index), expected);
+                assert_eq!(string.char_at_or(index, '\0'), expected.unwrap_or('\0'));
+            }
         }
 
         #[test]
-        fn test_insert_one<S: Data<String>>(string: ImString<S>) {
-            if !string.is_empty() && string.is_char_boundary(1) {
-                let mut string = string;
-                let length = string.len();
-                string.insert(1, 'h');
-                assert_eq!(string.len(), length + 1);
-                assert_eq!(string.chars().nth(1), Some('h'));
-            }
+        fn test_char_at_or<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("x€y").slice(1..);
+            assert_eq!(string.char_at_or(0, '?'), '€');
+            assert_eq!(string.char_at_or(3, '?'), 'y');
+            assert_eq!(string.char_at_or(1, '?'), '?');
+            assert_eq!(string.char_at_or(2, '?'), '?');
+            assert_eq!(string.char_at_or(4, '?'), '?');
+            assert_eq!(string.char_at_or(100, '?'), '?');
         }
 
         #[test]
-        fn test_insert_end<S: Data<String>>(string: ImString<S>) {
-            let mut string = string;
-            let length = string.len();
-            string.insert(length, 'h');
-            assert_eq!(string.len(), length + 1);
-            // FIXME
-            //assert_eq!(string.chars().nth(length), Some('h'));
+        fn test_chars_vec<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.chars_vec(), string.as_str().chars().collect::<Vec<_>>());
         }
 
         #[test]
-        fn test_insert_str_start<S: Data<String>>(string: ImString<S>) {
-            // test to make sure we can insert_str at the start
-            let original = string.as_str().to_string();
-            let mut string = string;
-            let inserted = "hello";
-            string.insert_str(0, inserted);
-            assert_eq!(string.len(), original.len() + inserted.len());
-            assert_eq!(&string[0..inserted.len()], inserted);
-            assert_eq!(&string[inserted.len()..], original);
+        fn test_chars_size_hint<S: Data<String>>(string: ImString<S>) {
+            let mut chars = string.chars();
+            loop {
+                let (lower, upper) = chars.size_hint();
+                let remaining = chars.clone().count();
+                assert!(lower <= remaining);
+                assert!(upper.unwrap() >= remaining);
+                if chars.next().is_none() {
+                    break;
+                }
+            }
         }
 
         #[test]
-        fn test_insert_str_end<S: Data<String>>(string: ImString<S>) {
-            // test to make sure we can insert_str at the end
-            let original = string.as_str().to_string();
-            let mut string = string;
-            let inserted = "hello";
-            string.insert_str(string.len(), inserted);
-            assert_eq!(string.len(), original.len() + inserted.len());
-            assert_eq!(&string[0..original.len()], original);
-            assert_eq!(&string[original.len()..], inserted);
+        fn test_from_raw_parts<S: Data<String>>(string: ImString<S>) {
+            let copy = ImString::from_raw_parts(string.raw_string(), string.raw_offset()).unwrap();
+            assert_eq!(copy, string);
+            let copy = unsafe {
+                ImString::from_raw_parts_unchecked(string.raw_string(), string.raw_offset())
+            };
+            assert_eq!(copy, string);
         }
 
         #[test]
-        fn test_is_empty<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(string.is_empty(), string.len() == 0);
-            assert_eq!(string.is_empty(), string.as_str().is_empty());
+        fn test_from_raw_parts_invalid<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("añb");
+            let raw = || string.raw_string();
+            assert_eq!(ImString::from_raw_parts(raw(), 0..4).unwrap(), "añb");
+            assert_eq!(ImString::from_raw_parts(raw(), 1..3).unwrap(), "ñ");
+            assert_eq!(ImString::from_raw_parts(raw(), 4..4).unwrap(), "");
+            assert_eq!(ImString::from_raw_parts(raw(), 5..5), Err(SliceError::StartOutOfBounds));
+            assert_eq!(ImString::from_raw_parts(raw(), 0..5), Err(SliceError::EndOutOfBounds));
+            #[allow(clippy::reversed_empty_ranges)]
+            let reversed = ImString::from_raw_parts(raw(), 3..1);
+            assert_eq!(reversed, Err(SliceError::EndBeforeStart));
+            assert_eq!(ImString::from_raw_parts(raw(), 2..3), Err(SliceError::StartNotAligned));
+            assert_eq!(ImString::from_raw_parts(raw(), 0..2), Err(SliceError::EndNotAligned));
         }
 
         #[test]
-        fn test_push<S: Data<String>>(string: ImString<S>) {
-            let mut string = string;
-            let mut std_string = string.as_str().to_string();
-            let c = 'c';
-            std_string.push(c);
-            string.push(c);
-            assert_eq!(string, std_string);
+        fn test_try_set_offset<S: Data<String>>() {
+            let mut string: ImString<S> = ImString::from("añb");
+            assert_eq!(string.try_set_offset(1..3), Ok(()));
+            assert_eq!(string, "ñ");
+            assert_eq!(string.try_set_offset(0..4), Ok(()));
+            assert_eq!(string, "añb");
+            assert_eq!(string.try_set_offset(4..4), Ok(()));
+            assert_eq!(string, "");
+            assert_eq!(string.try_set_offset(0..4), Ok(()));
         }
 
         #[test]
-        fn test_push_str<S: Data<String>>(string: ImString<S>) {
-            let mut string = string;
-            let mut std_string = string.as_str().to_string();
-            let s = "string";
-            std_string.push_str(s);
-            string.push_str(s);
-            assert_eq!(string, std_string);
+        fn test_try_set_offset_out_of_bounds<S: Data<String>>() {
+            let mut string: ImString<S> = ImString::from("añb");
+            assert_eq!(string.try_set_offset(5..5), Err(SliceError::StartOutOfBounds));
+            assert_eq!(string.try_set_offset(5..6), Err(SliceError::StartOutOfBounds));
+            assert_eq!(string.try_set_offset(0..5), Err(SliceError::EndOutOfBounds));
+            assert_eq!(string.try_set_offset(4..5), Err(SliceError::EndOutOfBounds));
+            assert_eq!(string.try_set_offset(2..3), Err(SliceError::StartNotAligned));
+            assert_eq!(string.try_set_offset(0..2), Err(SliceError::EndNotAligned));
+            assert_eq!(string, "añb");
         }
 
         #[test]
-        fn test_pop<S: Data<String>>(string: ImString<S>) {
-            let mut characters: Vec<char> = string.chars().collect();
-            let mut string = string;
+        fn test_chars_debug<S: Data<String>>(string: ImString<S>) {
+            let mut chars = string.chars();
+            let mut expected = string.as_str().chars();
             loop {
-                let c1 = characters.pop();
-                let c2 = string.pop();
-                assert_eq!(c1, c2);
-                if c1.is_none() {
+                assert_eq!(format!("{chars:?}"), format!("{expected:?}"));
+                if chars.next().is_none() {
                     break;
                 }
+                expected.next();
             }
         }
 
         #[test]
-        fn test_index_range_full<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(&string[..], &string.as_str()[..]);
+        fn test_char_iterators_debug<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("aüc");
+            let mut chars = string.chars();
+            assert_eq!(format!("{chars:?}"), "Chars(['a', 'ü', 'c'])");
+            chars.next();
+            chars.next();
+            assert_eq!(format!("{chars:?}"), "Chars(['c'])");
+            chars.next();
+            assert_eq!(format!("{chars:?}"), "Chars([])");
+
+            let mut indices = string.char_indices();
+            assert_eq!(
+                format!("{indices:?}"),
+                r#"CharIndices { offset: 0, remaining: "aüc" }"#
+            );
+            indices.next();
+            indices.next();
+            assert_eq!(
+                format!("{indices:?}"),
+                r#"CharIndices { offset: 3, remaining: "c" }"#
+            );
         }
 
         #[test]
-        fn test_index_range_from<S: Data<String>>(string: ImString<S>) {
-            for i in (0..string.len()).filter(|i| string.is_char_boundary(*i)) {
-                assert_eq!(&string[i..], &string.as_str()[i..]);
+        fn test_split_at_first<S: Data<String>>(string: ImString<S>) {
+            match string.split_at_first(|c| c == 'l') {
+                Some((head, tail)) => {
+                    assert_eq!(format!("{head}{tail}"), string.as_str());
+                    assert!(!head.contains('l'));
+                    assert!(tail.starts_with('l'));
+                }
+                None => assert!(!string.contains('l')),
             }
         }
 
         #[test]
-        fn test_index_range_to<S: Data<String>>(string: ImString<S>) {
-            for i in (0..string.len()).filter(|i| string.is_char_boundary(*i)) {
-                assert_eq!(&string[..i], &string.as_str()[..i]);
-            }
+        fn test_split_at_first_space<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("grüße an alle");
+            let (first, rest) = string.split_at_first(|c| c == ' ').unwrap();
+            assert_eq!((first.as_str(), rest.as_str()), ("grüße", " an alle"));
+            let (empty, rest) = string.split_at_first(|c| c == 'g').unwrap();
+            assert_eq!((empty.as_str(), rest.as_str()), ("", "grüße an alle"));
+            assert_eq!(string.split_at_first(|c| c.is_ascii_digit()), None);
+            let string: ImString<S> = ImString::new();
+            assert_eq!(string.split_at_first(|_| true), None);
         }
 
         #[test]
-        fn test_index_range_exclusive<S: Data<String>>(string: ImString<S>) {
-            for start in (0..string.len()).filter(|i| string.is_char_boundary(*i)) {
-                for end in (start..string.len()).filter(|i| string.is_char_boundary(*i)) {
-                    assert_eq!(&string[start..end], &string.as_str()[start..end]);
+        fn test_line_col_at<S: Data<String>>(string: ImString<S>) {
+            let (mut line, mut column) = (1, 1);
+            for (index, c) in string.as_str().char_indices() {
+                assert_eq!(string.line_col_at(index), (line, column));
+                if c == '\n' {
+                    line += 1;
+                    column = 1;
+                } else {
+                    column += 1;
                 }
             }
+            assert_eq!(string.line_col_at(string.len()), (line, column));
         }
 
         #[test]
-        fn test_index_range_inclusive<S: Data<String>>(string: ImString<S>) {
-            if !string.is_empty() {
-                for start in (0..string.len()-1).filter(|i| string.is_char_boundary(*i)) {
-                    for end in (start..string.len()-1).filter(|i| string.is_char_boundary(*i + 1)) {
-                        assert_eq!(&string[start..=end], &string.as_str()[start..=end]);
-                    }
-                }
-            }
+        fn test_line_col_at_multibyte<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("x\nä€\n\nend").slice(2..);
+            assert_eq!(string.line_col_at(0), (1, 1));
+            assert_eq!(string.line_col_at(2), (1, 2));
+            assert_eq!(string.line_col_at(5), (1, 3));
+            assert_eq!(string.line_col_at(6), (2, 1));
+            assert_eq!(string.line_col_at(7), (3, 1));
+            assert_eq!(string.line_col_at(9), (3, 3));
+            assert_eq!(string.line_col_at(10), (3, 4));
         }
 
         #[test]
-        fn test_into_bytes<S: Data<String>>(string: ImString<S>) {
-            let std_bytes = string.as_str().to_string().into_bytes();
-            let bytes = string.into_bytes();
-            assert_eq!(bytes, std_bytes);
+        fn test_char_range_to_byte_range<S: Data<String>>(string: ImString<S>) {
+            let count = string.chars().count();
+            assert_eq!(string.char_range_to_byte_range(0..0), Some(0..0));
+            assert_eq!(string.char_range_to_byte_range(0..count), Some(0..string.len()));
+            assert_eq!(string.char_range_to_byte_range(count..count), Some(string.len()..string.len()));
+            assert_eq!(string.char_range_to_byte_range(0..count + 1), None);
+            assert_eq!(string.char_range_to_byte_range(count + 1..count + 1), None);
         }
 
         #[test]
-        fn test_slice_all<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(string.slice(..), string);
+        fn test_char_range_to_byte_range_multibyte<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("xaß€😀b").slice(1..);
+            assert_eq!(string.char_range_to_byte_range(0..1), Some(0..1));
+            assert_eq!(string.char_range_to_byte_range(1..3), Some(1..6));
+            assert_eq!(string.char_range_to_byte_range(3..3), Some(6..6));
+            assert_eq!(string.char_range_to_byte_range(3..5), Some(6..11));
+            assert_eq!(string.char_range_to_byte_range(0..5), Some(0..11));
+            assert_eq!(string.char_range_to_byte_range(0..6), None);
+            #[allow(clippy::reversed_empty_ranges)]
+            let reversed = string.char_range_to_byte_range(3..2);
+            assert_eq!(reversed, None);
+            let range = string.char_range_to_byte_range(2..4).unwrap();
+            assert_eq!(string.slice(range), "€😀");
         }
 
         #[test]
-        fn test_slice_start<S: Data<String>>(string: ImString<S>) {
-            for end in 0..string.len() {
-                if string.is_char_boundary(end) {
-                    assert_eq!(string.slice(..end), string.as_str()[..end]);
-                }
-            }
+        fn test_prepend<S: Data<String>>(string: ImString<S>) {
+            let mut prepended = string.clone();
+            prepended.prepend("prefix");
+            assert_eq!(prepended, format!("prefix{string}"));
+            assert_eq!(string.len() + 6, prepended.len());
         }
 
         #[test]
-        fn test_slice_end<S: Data<String>>(string: ImString<S>) {
-            for start in 0..string.len() {
-                if string.is_char_boundary(start) {
-                    assert_eq!(string.slice(start..), string.as_str()[start..]);
-                }
-            }
+        fn test_prepend_sliced<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("hello, world");
+            let mut world = string.slice(7..);
+            world.prepend("big ");
+            assert_eq!(world, "big world");
+            assert_eq!(string, "hello, world");
+
+            let mut hello = string.slice(..5);
+            hello.prepend("");
+            assert_eq!(hello, "hello");
+            hello.prepend("¡");
+            assert_eq!(hello, "¡hello");
         }
 
         #[test]
-        fn test_slice_middle<S: Data<String>>(string: ImString<S>) {
-            for start in 0..string.len() {
-                if string.is_char_boundary(start) {
-                    for end in start..string.len() {
-                        if string.is_char_boundary(end) {
-                            assert_eq!(string.slice(start..end), string.as_str()[start..end]);
-                        }
-                    }
-                }
-            }
+        fn test_append<S: Data<String>>(string: ImString<S>) {
+            let mut appended = string.clone();
+            appended.append("suffix");
+            assert_eq!(appended, format!("{string}suffix"));
+
+            let mut pushed = string.clone();
+            pushed.push_str("suffix");
+            assert_eq!(appended, pushed);
         }
 
         #[test]
-        fn test_try_slice_all<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(string.try_slice(..).unwrap(), string);
+        fn test_append_sliced<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("hello, world");
+            let mut hello = string.slice(..5);
+            hello.append("!");
+            assert_eq!(hello, "hello!");
+            assert_eq!(string, "hello, world");
         }
 
         #[test]
-        fn test_try_slice_start<S: Data<String>>(string: ImString<S>) {
-            for end in 0..string.len() {
-                if string.is_char_boundary(end) {
-                    assert_eq!(string.try_slice(..end).unwrap(), string.as_str()[..end]);
-                } else {
-                    // cannot get slice with end in middle of UTF-8 multibyte sequence.
-                    assert_eq!(string.try_slice(..end), Err(SliceError::EndNotAligned));
+        fn test_match_ranges<S: Data<String>>(string: ImString<S>) {
+            for pat in ["a", "ü", "hello", "\n"] {
+                let ranges: Vec<_> = string.match_ranges(pat).collect();
+                assert_eq!(ranges.len(), string.matches(pat).count());
+                for range in ranges {
+                    assert_eq!(string.slice(range), pat);
                 }
             }
+        }
 
-            // cannot get slice with end pointing past the end of the string.
-            assert_eq!(string.try_slice(..string.len()+1), Err(SliceError::EndOutOfBounds));
+        #[test]
+        fn test_match_ranges_sliced<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("ß-ab-ßab-aab");
+            let sliced = string.slice(2..);
+            let ranges: Vec<_> = sliced.match_ranges("ab").collect();
+            assert_eq!(ranges, [1..3, 6..8, 10..12]);
+            for range in ranges {
+                assert_eq!(sliced.slice(range), "ab");
+            }
+            let mut ranges = sliced.match_ranges("ß");
+            assert_eq!(ranges.next(), Some(4..6));
+            assert_eq!(ranges.next(), None);
+            assert_eq!(sliced.match_ranges("xyz").count(), 0);
+            let ranges: Vec<_> = ImString::<S>::from("aaaa").match_ranges("aa").collect();
+            assert_eq!(ranges, [0..2, 2..4]);
         }
 
         #[test]
-        fn test_try_slice_end<S: Data<String>>(string: ImString<S>) {
-            for start in 0..string.len() {
-                if string.is_char_boundary(start) {
-                    assert_eq!(string.try_slice(start..).unwrap(), string.as_str()[start..]);
-                } else {
-                    // cannot get slice with end in middle of UTF-8 multibyte sequence.
-                    assert_eq!(string.try_slice(start..), Err(SliceError::StartNotAligned));
+        fn test_truncate_returning<S: Data<String>>(string: ImString<S>) {
+            for length in 0..string.len() {
+                if string.is_char_boundary(length) {
+                    let mut truncated = string.clone();
+                    let tail = truncated.truncate_returning(length).unwrap();
+                    assert_eq!(truncated, string[..length]);
+                    assert_eq!(tail, string[length..]);
                 }
             }
 
-            // cannot get slice with end pointing past the end of the string.
-            assert_eq!(string.try_slice(string.len()+1..), Err(SliceError::StartOutOfBounds));
+            for length in [string.len(), string.len() + 1] {
+                let mut truncated = string.clone();
+                assert_eq!(truncated.truncate_returning(length), None);
+                assert_eq!(truncated, string);
+            }
         }
 
         #[test]
-        fn test_write<S: Data<String>>() {
-            let mut string: ImString<S> = ImString::new();
-            string.write_str("Hello").unwrap();
-            string.write_char(',').unwrap();
-            string.write_char(' ').unwrap();
-            string.write_str("World").unwrap();
-            string.write_char('!').unwrap();
-            assert_eq!(string, "Hello, World!");
+        fn test_truncate_returning_sliced<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("hello, wörld");
+            let mut world = string.slice(7..);
+            assert_eq!(world.truncate_returning(3).unwrap(), "rld");
+            assert_eq!(world, "wö");
+            assert_eq!(world.truncate_returning(3), None);
+            assert_eq!(world, "wö");
+            assert_eq!(string, "hello, wörld");
+        }
 
+        #[test]
+        fn test_sort_lines<S: Data<String>>(string: ImString<S>) {
+            let sorted = string.sort_lines();
+            assert!(sorted.is_lines_sorted());
+            assert_eq!(sorted.line_count(), string.line_count());
+            assert_eq!(sorted.ends_with('\n'), string.ends_with('\n'));
+            let mut lines: Vec<&str> = string.as_str().lines().collect();
+            lines.sort();
+            assert_eq!(sorted.as_str().lines().collect::<Vec<_>>(), lines);
         }
 
         #[test]
-        fn test_add_assign<S: Data<String>>(string: ImString<S>) {
-            let mut std_string = string.as_str().to_string();
-            let mut string = string;
-            string += "hello";
-            std_string += "hello";
-            assert_eq!(string, std_string);
+        fn test_sort_lines_unsorted<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("pear\r\napple\nfig\napple\n");
+            assert!(!string.is_lines_sorted());
+            let sorted = string.sort_lines();
+            assert_eq!(sorted, "apple\napple\nfig\npear\n");
+            assert!(sorted.is_lines_sorted());
+
+            let string: ImString<S> = ImString::from("b\na");
+            assert_eq!(string.sort_lines(), "a\nb");
+            assert!(ImString::<S>::new().is_lines_sorted());
+            assert_eq!(ImString::<S>::new().sort_lines(), "");
         }
 
         #[test]
-        fn test_add<S: Data<String>>(string: ImString<S>) {
-            let std_string = string.as_str().to_string();
-            let std_string = std_string + "hello";
-            let string = string + "hello";
-            assert_eq!(string, std_string);
+        fn test_reverse_lines<S: Data<String>>(string: ImString<S>) {
+            let reversed = string.reverse_lines();
+            assert_eq!(reversed.ends_with('\n'), string.ends_with('\n'));
+            let mut lines: Vec<&str> = string.as_str().lines().collect();
+            lines.reverse();
+            assert_eq!(reversed.as_str().lines().collect::<Vec<_>>(), lines);
+            assert_eq!(reversed.reverse_lines(), string.normalize_newlines());
         }
 
         #[test]
-        fn test_to_socket_addrs<S: Data<String>>(_string: ImString<S>) {
-            #[cfg(all(feature = "std", not(miri)))]
-            {
-                let addrs = _string.to_socket_addrs().map(|s| s.collect::<Vec<_>>());
-                let str_addrs = _string.as_str().to_socket_addrs().map(|s| s.collect::<Vec<_>>());
-                match addrs {
-                    Ok(addrs) => assert_eq!(addrs, str_addrs.unwrap()),
-                    Err(_err) => assert!(str_addrs.is_err()),
-                }
+        fn test_reverse_lines_terminators<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("one\r\ntwo\nthree");
+            assert_eq!(string.reverse_lines(), "three\ntwo\none");
+            let string: ImString<S> = ImString::from("single line\n");
+            assert_eq!(string.reverse_lines(), "single line\n");
+            let string: ImString<S> = ImString::from("single line");
+            assert_eq!(string.reverse_lines(), "single line");
+        }
+
+        #[test]
+        fn test_split_keeping_separators<S: Data<String>>(string: ImString<S>) {
+            let separators = [' ', '\n', 'ü'];
+            let runs: Vec<ImString<S>> = string.split_keeping_separators(&separators).collect();
+            assert_eq!(runs.concat(), string.as_str());
+            assert!(runs.iter().all(|run| !run.is_empty()));
+            for pair in runs.windows(2) {
+                let first = pair[0].starts_with(&separators[..]);
+                let second = pair[1].starts_with(&separators[..]);
+                assert_ne!(first, second);
             }
         }
 
         #[test]
-        fn test_from_iterator_char<S: Data<String>>() {
-            let input = ['h', 'e', 'l', 'l', 'o'];
-            let string: ImString<S> = input.into_iter().collect();
-            assert_eq!(string, "hello");
+        fn test_split_keeping_separators_edges<S: Data<String>>() {
+            let string: ImString<S> = ImString::from(", a,b ,");
+            let runs: Vec<ImString<S>> = string.split_keeping_separators(&[',', ' ']).collect();
+            assert_eq!(runs, [", ", "a", ",", "b", " ,"]);
+
+            let runs: Vec<ImString<S>> = string.split_keeping_separators(&[]).collect();
+            assert_eq!(runs, [", a,b ,"]);
+
+            let empty: ImString<S> = ImString::new();
+            assert_eq!(empty.split_keeping_separators(&[',']).count(), 0);
+        }
+
+        #[test]
+        fn test_decode_escapes<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.escape_default().decode_escapes().unwrap(), string);
+            assert_eq!(string.escape_debug().decode_escapes().unwrap(), string);
+        }
+
+        #[test]
+        fn test_decode_escapes_invalid<S: Data<String>>() {
+            let decode = |string: &str| ImString::<S>::from(string).decode_escapes();
+            assert_eq!(decode(r"\x41\0\'").unwrap(), "A\0'");
+            assert_eq!(decode(r"\u{10ffff}").unwrap(), "\u{10ffff}");
+            assert_eq!(decode(r"trailing\"), Err(EscapeError::UnexpectedEnd));
+            assert_eq!(decode(r"\q"), Err(EscapeError::UnknownEscape));
+            assert_eq!(decode(r"\x4"), Err(EscapeError::UnexpectedEnd));
+            assert_eq!(decode(r"\x4g"), Err(EscapeError::InvalidHexEscape));
+            assert_eq!(decode(r"\xff"), Err(EscapeError::InvalidHexEscape));
+            assert_eq!(decode(r"\u41"), Err(EscapeError::InvalidUnicodeEscape));
+            assert_eq!(decode(r"\u{}"), Err(EscapeError::InvalidUnicodeEscape));
+            assert_eq!(decode(r"\u{d800}"), Err(EscapeError::InvalidUnicodeEscape));
+            assert_eq!(decode(r"\u{1234567}"), Err(EscapeError::InvalidUnicodeEscape));
+            assert_eq!(decode(r"\u{41"), Err(EscapeError::UnexpectedEnd));
+        }
+
+        #[test]
+        fn test_ensure_trailing_newline<S: Data<String>>(string: ImString<S>) {
+            let terminated = string.ensure_trailing_newline();
+            assert_eq!(terminated.ends_with_newline(), !string.is_empty());
+            assert_eq!(terminated.line_count(), string.line_count());
+            assert!(terminated.starts_with(string.as_str()));
+            assert_eq!(terminated.ensure_trailing_newline(), terminated);
+        }
+
+        #[test]
+        fn test_try_into_array<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("GET ");
+            let method: [u8; 4] = string.clone().try_into().unwrap();
+            assert_eq!(&method, b"GET ");
+
+            let result: Result<[u8; 3], _> = string.clone().try_into();
+            assert_eq!(result.unwrap_err(), string);
+            let result: Result<[u8; 5], _> = string.clone().try_into();
+            assert_eq!(result.unwrap_err(), string);
+
+            let empty: [u8; 0] = ImString::<S>::new().try_into().unwrap();
+            assert_eq!(empty, []);
         }
 
         #[test]
-        fn test_from_iterator_char_ref<S: Data<String>>() {
-            let input = ['h', 'e', 'l', 'l', 'o'];
-            let string: ImString<S> = input.iter().collect();
-            assert_eq!(string, "hello");
+        fn test_char_slices<S: Data<String>>(string: ImString<S>) {
+            let slices: Vec<(usize, ImString<S>)> = string.char_slices().collect();
+            assert_eq!(slices.len(), string.len_chars());
+            for ((index, slice), (expected_index, c)) in slices.iter().zip(string.char_indices()) {
+                assert_eq!(*index, expected_index);
+                assert_eq!(slice.as_str().chars().collect::<Vec<_>>(), [c]);
+            }
         }
 
         #[test]
-        fn test_from_iterator_str<S: Data<String>>() {
-            let input = ["hello", "world", "!"];
-            let string: ImString<S> = input.into_iter().collect();
-            assert_eq!(string, "helloworld!");
+        fn test_map_str<S: Data<String>>(string: ImString<S>) {
+            let upper: ImString<S> = string.map_str(str::to_uppercase);
+            assert_eq!(upper, string.to_uppercase());
+            let repeated: ImString<S> = string.map_str(|s| s.repeat(2));
+            assert_eq!(repeated.len(), 2 * string.len());
+            assert_eq!(string.map_str(str::to_string), string);
         }
 
         #[test]
-        fn test_extend_char<S: Data<String>>() {
-            let input = ['h', 'e', 'l', 'l', 'o'];
-            let mut string: ImString<S> = ImString::new();
-            string.extend(input.into_iter());
-            assert_eq!(string, "hello");
+        fn test_filter_map_chars<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.filter_map_chars(Some), string);
+            assert_eq!(string.filter_map_chars(|_| None), "");
+            let upper = string.filter_map_chars(|c| Some(c.to_ascii_uppercase()));
+            assert_eq!(upper, string.to_ascii_uppercase());
         }
 
         #[test]
-        fn test_extend_char_ref<S: Data<String>>() {
-            let input = ['h', 'e', 'l', 'l', 'o'];
-            let mut string: ImString<S> = ImString::new();
-            string.extend(input.into_iter());
-            assert_eq!(string, "hello");
+        fn test_filter_map_chars_content<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("Hello, World!");
+            let consonants = string.filter_map_chars(|c| (!"aeiouAEIOU".contains(c)).then_some(c));
+            assert_eq!(consonants, "Hll, Wrld!");
+
+            let string: ImString<S> = ImString::from("call 555-0123 now");
+            let masked = string.filter_map_chars(|c| match c {
+                '0'..='9' => Some('#'),
+                c => Some(c),
+            });
+            assert_eq!(masked, "call ###-#### now");
         }
 
         #[test]
-        fn test_extend_str<S: Data<String>>() {
-            let input = ["hello", "world", "!"];
-            let mut string: ImString<S> = ImString::new();
-            string.extend(input.into_iter());
-            assert_eq!(string, "helloworld!");
+        fn test_split_once_char<S: Data<String>>(string: ImString<S>) {
+            for c in ['a', 'ü', '\n', 'l', '\u{1f603}', 'z'] {
+                let split = string.split_once_char(c);
+                let expected = string.as_str().split_once(c);
+                assert_eq!(
+                    split.as_ref().map(|(a, b)| (a.as_str(), b.as_str())),
+                    expected
+                );
+            }
         }
 
         #[test]
-        fn test_from_utf8_lossy<S: Data<String>>() {
-            let string: ImString<S> = ImString::from_utf8_lossy(b"hello");
-            assert_eq!(string, "hello");
+        fn test_split_once_char_edges<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("=value");
+            let (key, value) = string.split_once_char('=').unwrap();
+            assert_eq!(key, "");
+            assert_eq!(value, "value");
+
+            let string: ImString<S> = ImString::from("key=");
+            let (key, value) = string.split_once_char('=').unwrap();
+            assert_eq!(key, "key");
+            assert_eq!(value, "");
+
+            let string: ImString<S> = ImString::from("a→b→c").slice(1..);
+            let (before, after) = string.split_once_char('→').unwrap();
+            assert_eq!(before, "");
+            assert_eq!(after, "b→c");
+
+            assert_eq!(ImString::<S>::from("no separator").split_once_char('='), None);
+            assert_eq!(ImString::<S>::new().split_once_char('='), None);
         }
 
         #[test]
-        fn test_from_utf8_unchecked<S: Data<String>>() {
-            let string: ImString<S> = unsafe {
-                ImString::from_utf8_unchecked(b"hello".to_vec())
-            };
-            assert_eq!(string, "hello");
+        fn test_with_offset<S: Data<String>>(string: ImString<S>) {
+            // offsets are relative to the backing string, so compare against a root string
+            let string: ImString<S> = ImString::from(string.as_str());
+            for start in 0..=string.len() + 1 {
+                for end in [start, start + 1, start + 4, string.len(), string.len() + 1] {
+                    let expected = string.try_slice(start..end);
+                    let result = string.with_offset(Range { start, end });
+                    assert_eq!(result, expected);
+                }
+            }
         }
 
         #[test]
-        fn test_borrow<S: Data<String>>(string: ImString<S>) {
-            let s: &str = string.borrow();
-            assert_eq!(s, string.as_str());
+        fn test_with_offset_backing<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("grüße, world");
+            let world = string.slice(9..);
+            assert_eq!(world.with_offset(0..7).unwrap(), "grüße");
+            assert_eq!(world.with_offset(9..14).unwrap(), "world");
+            assert_eq!(world, "world");
+            assert_eq!(world.with_offset(0..15), Err(SliceError::EndOutOfBounds));
+            assert_eq!(world.with_offset(15..15), Err(SliceError::StartOutOfBounds));
+            assert_eq!(world.with_offset(3..7), Err(SliceError::StartNotAligned));
+            assert_eq!(world.with_offset(0..3), Err(SliceError::EndNotAligned));
+            assert_eq!(
+                world.with_offset(Range { start: 5, end: 2 }),
+                Err(SliceError::EndBeforeStart)
+            );
         }
 
         #[test]
-        fn test_as_ref_str<S: Data<String>>(string: ImString<S>) {
-            let s: &str = string.as_ref();
-            assert_eq!(s, string.as_str());
+        fn test_lines_in_common<S: Data<String>>(string: ImString<S>) {
+            let lines: Vec<&str> = string.as_str().lines().collect();
+            assert_eq!(string.lines_in_common(&string), lines);
+            assert!(string.lines_in_common(&ImString::new()).is_empty());
         }
 
         #[test]
-        fn test_as_ref_bytes<S: Data<String>>(string: ImString<S>) {
-            let s: &[u8] = string.as_ref();
-            assert_eq!(s, string.as_bytes());
+        fn test_lines_in_common_shared<S: Data<String>>() {
+            let old: ImString<S> = ImString::from("fn main() {\n    a();\n    b();\n}\n    a();\n");
+            let new: ImString<S> = ImString::from("fn main() {\r\n    b();\n    c();\n}");
+            let common = old.lines_in_common(&new);
+            assert_eq!(common, ["fn main() {", "    b();", "}"]);
+            assert_eq!(common[1].offset, 21..29);
+            assert_eq!(new.lines_in_common(&old), ["fn main() {", "    b();", "}"]);
         }
 
         #[test]
-        fn test_as_ref_path<S: Data<String>>(_string: ImString<S>) {
-            #[cfg(feature = "std")]
-            {
-                let s: &Path = _string.as_ref();
-                assert_eq!(s, _string.as_str().as_ref() as &Path);
+        fn test_preview<S: Data<String>>(string: ImString<S>) {
+            let chars = string.len_chars();
+            for max_chars in [0, 1, 5, chars.saturating_sub(1), chars, chars + 1] {
+                let preview = string.preview(max_chars);
+                assert_eq!(preview, string.display_elided(max_chars).to_string());
+                if max_chars < chars {
+                    assert_eq!(preview.len_chars(), max_chars + 1);
+                    assert!(preview.ends_with('…'));
+                } else {
+                    assert_eq!(preview, string);
+                }
             }
         }
 
         #[test]
-        fn test_as_ref_os_str<S: Data<String>>(_string: ImString<S>) {
-            #[cfg(feature = "std")]
-            {
-                let s: &OsStr = _string.as_ref();
-                assert_eq!(s, _string.as_str().as_ref() as &OsStr);
+        fn test_validate_spans<S: Data<String>>(string: ImString<S>) {
+            let spans: Vec<Range<usize>> = (0..=string.len() + 1)
+                .flat_map(|start| [start..start, start..start + 1, start..string.len()])
+                .collect();
+            let expected = spans
+                .iter()
+                .enumerate()
+                .find_map(|(index, span)| string.try_slice(span.clone()).err().map(|e| (index, e)));
+            match expected {
+                Some(error) => assert_eq!(string.validate_spans(&spans), Err(error)),
+                None => assert_eq!(string.validate_spans(&spans), Ok(())),
             }
         }
 
         #[test]
-        fn test_deref_mut<S: Data<String>>(string: ImString<S>) {
-            let mut string = string;
-            let data = string.as_str().to_string();
-            let mutable: &mut str = string.deref_mut();
-            assert_eq!(&*mutable, &data);
+        fn test_validate_spans_mid_char<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("a€b").slice(1..);
+            assert_eq!(string.validate_spans(&[]), Ok(()));
+            assert_eq!(string.validate_spans(&[0..3, 3..4, 0..4, 4..4]), Ok(()));
+            assert_eq!(
+                string.validate_spans(&[0..3, 3..4, 1..4, 0..2]),
+                Err((2, SliceError::StartNotAligned))
+            );
+            assert_eq!(
+                string.validate_spans(&[0..2, 0..3]),
+                Err((0, SliceError::EndNotAligned))
+            );
+            assert_eq!(
+                string.validate_spans(&[0..4, 0..5]),
+                Err((1, SliceError::EndOutOfBounds))
+            );
         }
 
         #[test]
-        fn test_as_mut<S: Data<String>>(string: ImString<S>) {
-            let mut string = string;
-            let data = string.as_str().to_string();
-            let mutable: &mut str = string.as_mut();
-            assert_eq!(&*mutable, &data);
+        fn test_push_str_checked<S: Data<String>>(string: ImString<S>) {
+            let mut pushed = string.clone();
+            pushed.push_str_checked("suffix").unwrap();
+            assert_eq!(pushed, format!("{string}suffix"));
+            assert_eq!(string, string.as_str().to_string());
+
+            let mut expected = string.clone();
+            expected.push_str("suffix");
+            assert_eq!(pushed, expected);
         }
 
         #[test]
-        fn test_borrow_mut<S: Data<String>>(string: ImString<S>) {
-            let mut string = string;
-            let data = string.as_str().to_string();
-            let mutable: &mut str = string.borrow_mut();
-            assert_eq!(&*mutable, &data);
+        fn test_push_str_checked_sliced<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("hello, world");
+            let mut hello = string.slice(..5);
+            hello.push_str_checked("!").unwrap();
+            assert_eq!(hello, "hello!");
+            let mut world = string.slice(7..);
+            world.push_str_checked("!").unwrap();
+            assert_eq!(world, "world!");
+            assert_eq!(string, "hello, world");
+
+            let mut unique: ImString<S> = ImString::from("unique");
+            unique.push_str_checked("").unwrap();
+            unique.push_str_checked(" string").unwrap();
+            assert_eq!(unique, "unique string");
         }
 
         #[test]
-        fn test_partial_eq<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(string, string.as_str());
-            assert_eq!(string, string.to_string());
-            assert_eq!(string, string);
+        fn test_split_first_word<S: Data<String>>(string: ImString<S>) {
+            let mut words = string.split_whitespace();
+            match string.split_first_word() {
+                Some((word, rest)) => {
+                    assert_eq!(Some(word.as_str()), words.next());
+                    assert!(rest.split_whitespace().eq(words));
+                    assert_eq!(rest, rest.trim_start());
+                }
+                None => assert_eq!(words.next(), None),
+            }
         }
 
         #[test]
-        fn test_partial_ord<S: Data<String>>(string: ImString<S>) {
-            let other = ImString::from("test");
-            assert_eq!(string.as_str().partial_cmp(other.as_str()), string.partial_cmp(&other));
-            assert_eq!(other.as_str().partial_cmp(string.as_str()), other.partial_cmp(&string));
+        fn test_split_first_word_commands<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("quit");
+            let (word, rest) = string.split_first_word().unwrap();
+            assert_eq!(word, "quit");
+            assert_eq!(rest, "");
+
+            let string: ImString<S> = ImString::from("echo hello  world ");
+            let (word, rest) = string.split_first_word().unwrap();
+            assert_eq!(word, "echo");
+            assert_eq!(rest, "hello  world ");
+
+            let string: ImString<S> = ImString::from("\t\n  go\u{3000}north");
+            let (word, rest) = string.split_first_word().unwrap();
+            assert_eq!(word, "go");
+            assert_eq!(rest, "north");
+
+            assert_eq!(ImString::<S>::new().split_first_word(), None);
+            assert_eq!(ImString::<S>::from("   ").split_first_word(), None);
         }
 
         #[test]
-        fn test_ord<S: Data<String>>(string: ImString<S>) {
-            let other = ImString::from("test");
-            assert_eq!(string.as_str().cmp(other.as_str()), string.cmp(&other));
-            assert_eq!(other.as_str().cmp(string.as_str()), other.cmp(&string));
+        fn test_try_slice_detailed<S: Data<String>>(string: ImString<S>) {
+            for start in 0..=string.len() + 1 {
+                for end in [start, start + 1, string.len(), string.len() + 1] {
+                    let detailed = string.try_slice_detailed(start..end);
+                    match string.try_slice(start..end) {
+                        Ok(slice) => assert_eq!(detailed.unwrap(), slice),
+                        Err(error) => {
+                            let detailed = detailed.unwrap_err();
+                            assert_eq!(detailed.kind(), error);
+                            assert_eq!(detailed.range(), start..end);
+                            assert_eq!(detailed.length(), string.len());
+                            let message = detailed.to_string();
+                            assert!(message.contains(&format!("{start}..{end}")));
+                            assert!(message.contains(&format!("length {}", string.len())));
+                        }
+                    }
+
+                    // an inclusive end is the same as an exclusive end one further
+                    let inclusive = string.try_slice_detailed(start..=end);
+                    assert_eq!(inclusive, string.try_slice_detailed(start..end + 1));
+                }
+            }
         }
 
         #[test]
-        fn test_from<S: Data<String>>(string: ImString<S>) {
-            let std_string: String = string.clone().into();
-            assert_eq!(string, std_string);
+        fn test_try_slice_inclusive<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("hello");
+            assert_eq!(string.try_slice(0..=2).unwrap(), "hel");
+            assert_eq!(string.try_slice(..=0).unwrap(), "h");
+            assert_eq!(string.try_slice(1..=4).unwrap(), "ello");
+            assert_eq!(string.slice(..=4), "hello");
+            let error = string.try_slice_detailed(2..=5).unwrap_err();
+            assert_eq!(error.kind(), SliceError::EndOutOfBounds);
+            assert_eq!(error.range(), 2..6);
+            assert_eq!(
+                string.try_slice(..=usize::MAX),
+                Err(SliceError::EndOutOfBounds)
+            );
+
+            let mut string: ImString<S> = ImString::from("añb");
+            assert_eq!(string.try_set_offset(1..=2), Ok(()));
+            assert_eq!(string, "ñ");
+            assert_eq!(string.try_set_offset(..=0), Ok(()));
+            assert_eq!(string, "a");
+            assert_eq!(string.try_set_offset(..=1), Err(SliceError::EndNotAligned));
         }
 
         #[test]
-        fn test_raw_offset<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(string.offset, string.raw_offset());
+        fn test_from_utf16_bytes<S: Data<String>>(string: ImString<S>) {
+            let units: Vec<u16> = string.encode_utf16().collect();
+            let le: Vec<u8> = units.iter().flat_map(|unit| unit.to_le_bytes()).collect();
+            let be: Vec<u8> = units.iter().flat_map(|unit| unit.to_be_bytes()).collect();
+            assert_eq!(ImString::<S>::from_utf16le_bytes(&le).unwrap(), string);
+            assert_eq!(ImString::<S>::from_utf16be_bytes(&be).unwrap(), string);
         }
 
         #[test]
-        fn test_raw_string<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(string.string.get(), string.raw_string().get());
+        fn test_from_utf16_bytes_invalid<S: Data<String>>() {
+            // "ok" followed by a lone high surrogate
+            let le = [0x6F, 0x00, 0x6B, 0x00, 0x00, 0xD8];
+            let be = [0x00, 0x6F, 0x00, 0x6B, 0xD8, 0x00];
+            assert_eq!(ImString::<S>::from_utf16le_bytes(&le[..4]).unwrap(), "ok");
+            assert_eq!(ImString::<S>::from_utf16be_bytes(&be[..4]).unwrap(), "ok");
+            let error = ImString::<S>::from_utf16le_bytes(&le).unwrap_err();
+            match error {
+                Utf16BytesError::Invalid(error) => assert_eq!(error.unpaired_surrogate(), 0xD800),
+                error => panic!("unexpected error {error:?}"),
+            }
+            let error = ImString::<S>::from_utf16be_bytes(&be).unwrap_err();
+            assert!(matches!(error, Utf16BytesError::Invalid(_)));
+
+            // odd number of bytes
+            assert_eq!(
+                ImString::<S>::from_utf16le_bytes(&le[..3]),
+                Err(Utf16BytesError::OddLength)
+            );
+            assert_eq!(
+                ImString::<S>::from_utf16be_bytes(&be[..1]),
+                Err(Utf16BytesError::OddLength)
+            );
+            // odd length is reported even if the complete code units are invalid
+            assert_eq!(
+                ImString::<S>::from_utf16le_bytes(&le[..5]),
+                Err(Utf16BytesError::OddLength)
+            );
+            assert_eq!(ImString::<S>::from_utf16le_bytes(&[]).unwrap(), "");
         }
 
         #[test]
-        fn into_std_string<S: Data<String>>(string: ImString<S>) {
-            let std_clone = string.as_str().to_string();
-            let std_string = string.into_std_string();
-            assert_eq!(std_clone, std_string);
+        fn test_eq_any<S: Data<String>>(string: ImString<S>) {
+            assert_eq!(string.eq_any(&[]), None);
+            assert_eq!(string.eq_any(&[string.as_str()]), Some(0));
+            let other = format!("{string}!");
+            assert_eq!(string.eq_any(&[&other, string.as_str(), string.as_str()]), Some(1));
+            assert_eq!(string.eq_any(&[&other]), None);
         }
 
         #[test]
-        fn test_truncate<S: Data<String>>(string: ImString<S>) {
-            let mut clone = string.as_str().to_string();
-            let mut string = string;
+        fn test_eq_any_options<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("GET /index.html").slice(..3);
+            assert_eq!(string.eq_any(&["POST", "GET", "PUT"]), Some(1));
+            assert_eq!(string.eq_any(&["get", "GE", "GET "]), None);
+        }
 
-            for length in (0..string.len()).rev() {
-                if string.is_char_boundary(length) {
-                    string.truncate(length);
-                    clone.truncate(length);
-                    assert_eq!(string, clone);
-                }
+        #[test]
+        fn test_lines_with_endings<S: Data<String>>(string: ImString<S>) {
+            let lines: Vec<_> = string.lines_with_endings().collect();
+            let expected: Vec<_> = string.as_str().lines().collect();
+            assert_eq!(lines.len(), expected.len());
+            let mut joined = String::new();
+            for ((line, ending), expected) in lines.iter().zip(expected) {
+                assert_eq!(line, expected);
+                joined.push_str(line);
+                joined.push_str(ending.as_str());
             }
+            assert_eq!(string, joined);
         }
 
         #[test]
-        fn test_str_ref<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(string, string.str_ref(string.as_str()));
+        fn test_lines_with_endings_mixed<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("first\r\nsecond\n\r\nfourth\r\r\nlast");
+            let lines: Vec<_> = string.lines_with_endings().collect();
+            assert_eq!(
+                lines,
+                [
+                    (ImString::from("first"), LineEnding::CrLf),
+                    (ImString::from("second"), LineEnding::Lf),
+                    (ImString::from(""), LineEnding::CrLf),
+                    (ImString::from("fourth\r"), LineEnding::CrLf),
+                    (ImString::from("last"), LineEnding::None),
+                ]
+            );
+
+            let string: ImString<S> = ImString::from("first\nsecond\r\n");
+            let endings: Vec<_> = string.lines_with_endings().map(|(_, e)| e).collect();
+            assert_eq!(endings, [LineEnding::Lf, LineEnding::CrLf]);
+            assert_eq!(ImString::<S>::new().lines_with_endings().count(), 0);
         }
 
         #[test]
-        fn test_try_str_ref<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(string, string.try_str_ref(string.as_str()).unwrap());
-            assert_eq!(string.try_str_ref("test"), None);
+        fn test_byte_positions<S: Data<String>>(string: ImString<S>) {
+            for byte in [b'\n', b' ', b'a', 0xe2] {
+                let positions = string.byte_positions(byte);
+                let count = string.as_bytes().iter().filter(|b| **b == byte).count();
+                assert_eq!(positions.len(), count);
+                assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+                assert!(positions.iter().all(|p| string.as_bytes()[*p] == byte));
+            }
         }
 
         #[test]
-        fn test_slice_ref<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(string, string.slice_ref(string.as_bytes()));
+        fn test_byte_positions_lines<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("# header\nfirst\nsecond\n\nlast");
+            let string = string.slice(9..);
+            let newlines = string.byte_positions(b'\n');
+            assert_eq!(newlines, [5, 12, 13]);
+            assert_eq!(string.slice(..newlines[0]), "first");
+            assert_eq!(string.slice(newlines[0] + 1..newlines[1]), "second");
+            assert_eq!(string.slice(newlines[1] + 1..newlines[2]), "");
+            assert_eq!(string.slice(newlines[2] + 1..), "last");
+            assert_eq!(string.byte_positions(b'#'), []);
         }
 
         #[test]
-        fn test_try_slice_ref<S: Data<String>>(string: ImString<S>) {
-            assert_eq!(string, string.try_slice_ref(string.as_bytes()).unwrap());
-            assert_eq!(string.try_slice_ref(b"test"), None);
+        fn test_dedup_lines<S: Data<String>>(string: ImString<S>) {
+            let deduped = string.dedup_lines();
+            let mut lines: Vec<&str> = string.as_str().lines().collect();
+            lines.dedup();
+            assert_eq!(deduped.as_str().lines().collect::<Vec<_>>(), lines);
+        }
+
+        #[test]
+        fn test_dedup_lines_duplicates<S: Data<String>>() {
+            let string: ImString<S> = ImString::from("one\none\r\none\ntwo\ntwo");
+            assert_eq!(string.dedup_lines(), "one\ntwo\n");
+
+            let string: ImString<S> = ImString::from("one\ntwo\none\ntwo\n");
+            assert_eq!(string.dedup_lines(), "one\ntwo\none\ntwo\n");
+
+            // a trailing carriage return without a newline is part of the line
+            let string: ImString<S> = ImString::from("a\na\na\r");
+            assert_eq!(string.dedup_lines(), "a\na\r");
+        }
+    }
+
+    #[test]
+    fn test_display_elided() {
+        let string: ImString<Threadsafe> = ImString::from("short");
+        assert_eq!(format!("{}", string.display_elided(5)), "short");
+        assert_eq!(format!("{}", string.display_elided(10)), "short");
+
+        let string: ImString<Threadsafe> = ImString::from("grüße aus köln");
+        assert_eq!(format!("{}", string.display_elided(5)), "grüße…");
+        assert_eq!(format!("{}", string.display_elided(0)), "…");
+        assert_eq!(format!("{}", string.display_elided(14)), "grüße aus köln");
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_slice_panic_message() {
+        fn message(slice: impl FnOnce() -> ImString<Threadsafe>) -> String {
+            let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(slice)).unwrap_err();
+            panic.downcast_ref::<String>().unwrap().clone()
+        }
+
+        let string: ImString<Threadsafe> = ImString::from("hello");
+        assert_eq!(
+            message(|| string.slice(2..10)),
+            "slice range 2..10 is invalid for ImString of length 5: end offset out of bounds"
+        );
+        assert_eq!(
+            message(|| string.slice(7..)),
+            "slice range 7.. is invalid for ImString of length 5: start offset out of bounds"
+        );
+        assert_eq!(
+            message(|| string.slice(Range { start: 4, end: 2 })),
+            "slice range 4..2 is invalid for ImString of length 5: end offset before start offset"
+        );
+        assert_eq!(
+            message(|| string.slice((Bound::Excluded(usize::MAX), Bound::Unbounded))),
+            format!(
+                "slice range {}.. is invalid for ImString of length 5: start offset out of bounds",
+                usize::MAX
+            )
+        );
+
+        let string: ImString<Threadsafe> = ImString::from("añb");
+        let message = message(|| string.slice(..2));
+        assert!(message.contains("..2"));
+        assert!(message.contains("length 4"));
+        assert!(message.contains("multibyte"));
+    }
+
+    #[test]
+    fn test_from_utf8_slice() {
+        let bytes: Arc<[u8]> = Arc::from("grüße".as_bytes());
+        let string: ImString<Threadsafe> = ImString::from_utf8_slice(&bytes).unwrap();
+        assert_eq!(string, "grüße");
+        assert_eq!(&*bytes, string.as_bytes());
+
+        let bytes: Arc<[u8]> = Arc::from(&b"Hello \xF0\x90\x80World"[..]);
+        let error = ImString::<Threadsafe>::from_utf8_slice(&bytes).unwrap_err();
+        assert_eq!(error.valid_up_to(), 6);
+    }
+
+    #[test]
+    fn test_dedup_lines_shares() {
+        let string: ImString<Threadsafe> = ImString::from("one\ntwo\none\n");
+        let deduped = string.dedup_lines();
+        assert_eq!(deduped, string);
+        assert!(deduped.string.ptr_eq(&string.string));
+    }
+
+    #[test]
+    #[cfg(all(feature = "std", unix))]
+    fn test_path_components() {
+        let path: ImString<Threadsafe> = ImString::from("/home//user/./docs/../notes.txt/");
+        let components: Vec<_> = path.path_components().collect();
+        assert_eq!(components, ["/", "home", "user", "docs", "..", "notes.txt"]);
+        for index in [1, 2, 3, 5] {
+            assert!(components[index].string.ptr_eq(&path.string));
+        }
+
+        let path: ImString<Threadsafe> = ImString::from("relative/path");
+        let components: Vec<_> = path.path_components().collect();
+        assert_eq!(components, ["relative", "path"]);
+        assert_eq!(components[1].raw_offset(), 9..13);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_char_counts() {
+        let string: ImString<Threadsafe> = ImString::from("mississippi grüße");
+        let counts = string.char_counts();
+        assert_eq!(counts[&'s'], 4);
+        assert_eq!(counts[&'i'], 4);
+        assert_eq!(counts[&'ü'], 1);
+        assert_eq!(counts.get(&'x'), None);
+        assert_eq!(counts.values().sum::<usize>(), string.len_chars());
+        assert!(ImString::<Threadsafe>::new().char_counts().is_empty());
+    }
+
+    #[test]
+    fn test_ensure_trailing_newline_shares() {
+        let string: ImString<Threadsafe> = ImString::from("first\nsecond\n");
+        let terminated = string.ensure_trailing_newline();
+        assert!(terminated.string.ptr_eq(&string.string));
+
+        let string: ImString<Threadsafe> = ImString::from("first\nsecond");
+        let terminated = string.ensure_trailing_newline();
+        assert_eq!(terminated, "first\nsecond\n");
+        assert_eq!(string, "first\nsecond");
+
+        let string: ImString<Threadsafe> = ImString::new();
+        assert!(!string.ends_with_newline());
+        assert_eq!(string.ensure_trailing_newline(), "");
+    }
+
+    #[test]
+    fn test_char_slices_shares() {
+        let string: ImString<Threadsafe> = ImString::from("x€😀y").slice(1..);
+        let slices: Vec<_> = string.char_slices().collect();
+        assert_eq!(slices.len(), 3);
+        assert_eq!(slices[0], (0, ImString::from("€")));
+        assert_eq!(slices[1], (3, ImString::from("😀")));
+        assert_eq!(slices[2], (7, ImString::from("y")));
+        for (index, slice) in &slices {
+            assert!(slice.string.ptr_eq(&string.string));
+            assert_eq!(slice.offset.start, string.offset.start + index);
+        }
+    }
+
+    #[test]
+    fn test_lines_with_endings_shares() {
+        let string: ImString<Threadsafe> = ImString::from("> one\r\ntwo\nthree").slice(2..);
+        let lines: Vec<_> = string.lines_with_endings().collect();
+        let starts = [0, 5, 9];
+        assert_eq!(lines.len(), starts.len());
+        for ((line, _), start) in lines.iter().zip(starts) {
+            assert!(line.string.ptr_eq(&string.string));
+            assert_eq!(line.offset.start, string.offset.start + start);
+        }
+    }
+
+    #[test]
+    fn test_into_std_string_without_try_unwrap() {
+        // backing which relies on the default implementation of try_unwrap()
+        #[derive(Clone)]
+        struct Unique(Rc<String>);
+
+        impl Data<String> for Unique {
+            fn new(value: String) -> Self {
+                Unique(Rc::new(value))
+            }
+
+            fn get(&self) -> &String {
+                &self.0
+            }
+
+            fn get_mut(&mut self) -> Option<&mut String> {
+                Rc::get_mut(&mut self.0)
+            }
+
+            fn ptr_eq(&self, other: &Self) -> bool {
+                Rc::ptr_eq(&self.0, &other.0)
+            }
+        }
+
+        let string: ImString<Unique> = ImString::from("hello, world");
+        let pointer = string.as_ptr();
+        let world = string.slice(7..);
+        drop(string);
+        let world = world.into_std_string();
+        assert_eq!(world, "world");
+        assert_eq!(world.as_ptr(), pointer);
+
+        let string: ImString<Unique> = ImString::from("hello, world");
+        let world = string.slice(7..).into_std_string();
+        assert_eq!(world, "world");
+        assert_ne!(world.as_ptr(), string.as_ptr());
+        assert_eq!(string, "hello, world");
+    }
+
+    #[test]
+    fn test_into_std_string_reuses() {
+        let string: ImString<Threadsafe> = ImString::from("hello, world");
+        let pointer = string.as_ptr();
+        let world = string.slice(7..);
+        drop(string);
+        let world = world.into_std_string();
+        assert_eq!(world, "world");
+        assert_eq!(world.as_ptr(), pointer);
+
+        let string: ImString<Threadsafe> = ImString::from("hello, world");
+        let world = string.slice(7..).into_std_string();
+        assert_eq!(world, "world");
+        assert_ne!(world.as_ptr(), string.as_ptr());
+        assert_eq!(string, "hello, world");
+    }
+
+    #[test]
+    fn test_preview_shares() {
+        let string: ImString<Threadsafe> = ImString::from("short");
+        let preview = string.preview(5);
+        assert_eq!(preview, "short");
+        assert!(preview.string.ptr_eq(&string.string));
+
+        let string: ImString<Threadsafe> = ImString::from("grüße aus köln");
+        let preview = string.preview(7);
+        assert_eq!(preview, "grüße a…");
+        assert_eq!(preview.len_chars(), 8);
+        assert!(!preview.string.ptr_eq(&string.string));
+    }
+
+    /// Hasher which records everything that is written to it.
+    #[derive(Default)]
+    struct RecordingHasher(Vec<u8>);
+
+    impl Hasher for RecordingHasher {
+        fn finish(&self) -> u64 {
+            self.0.len() as u64
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes);
+        }
+    }
+
+    fn hash_bytes<T: Hash + ?Sized>(value: &T) -> Vec<u8> {
+        let mut hasher = RecordingHasher::default();
+        value.hash(&mut hasher);
+        hasher.0
+    }
+
+    #[test]
+    fn test_hash_across_backings() {
+        for string in test_strings::<Threadsafe>() {
+            let expected = hash_bytes(string.as_str());
+            assert_eq!(hash_bytes(&string), expected);
+            assert_eq!(hash_bytes(&string.reparent::<Local>()), expected);
+            assert_eq!(hash_bytes(&string.reparent::<Cloned<String>>()), expected);
+            assert_eq!(hash_bytes(&string.reparent::<Box<String>>()), expected);
+            #[cfg(feature = "triomphe")]
+            assert_eq!(
+                hash_bytes(&string.reparent::<triomphe::Arc<String>>()),
+                expected
+            );
+
+            // a slice with the same content hashes like the string itself
+            let padded: ImString<Local> = ImString::from(format!("[{string}]"));
+            let sliced = padded.slice(1..padded.len() - 1);
+            assert_eq!(sliced, string);
+            assert_eq!(hash_bytes(&sliced), expected);
+        }
+    }
+
+    #[test]
+    fn test_push_str_checked_in_place() {
+        let mut string: ImString<Threadsafe> = ImString::from("in place");
+        let pointer = string.as_ptr();
+        string.push_str_checked("!").unwrap();
+        assert_eq!(string, "in place!");
+        assert_eq!(string.as_ptr(), pointer);
+    }
+
+    #[test]
+    fn test_partial_eq_same_backing() {
+        let string: ImString<Threadsafe> = ImString::from("hello hello");
+        assert_eq!(string, string.clone());
+        assert_eq!(string.slice(..5), string.slice(..5));
+        assert_eq!(string.slice(..5), string.slice(6..));
+        assert_ne!(string.slice(..5), string.slice(..4));
+        assert_ne!(string.slice(..0), string.slice(..1));
+        assert_eq!(string.slice(3..3), string.slice(7..7));
+
+        // backings which clone the string have different pointers, but still compare equal
+        let cloned: ImString<Cloned<String>> = ImString::from("hello");
+        assert_eq!(cloned, cloned.clone());
+        assert_eq!(cloned, string.slice(6..));
+    }
+
+    #[test]
+    fn test_truncate_returning_shares() {
+        let mut string: ImString<Threadsafe> = ImString::from("hello world");
+        let tail = string.truncate_returning(5).unwrap();
+        assert_eq!(string, "hello");
+        assert_eq!(tail, " world");
+        assert!(tail.string.ptr_eq(&string.string));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_truncate_returning_mid_char() {
+        let mut string: ImString<Threadsafe> = ImString::from("añb");
+        string.truncate_returning(2);
+    }
+
+    #[test]
+    fn test_to_ascii_safe_shares() {
+        let string: ImString<Threadsafe> = ImString::from("hello, world").slice(7..);
+        let safe = string.to_ascii_safe('?');
+        assert_eq!(safe, "world");
+        assert!(safe.string.ptr_eq(&string.string));
+    }
+
+    #[test]
+    #[should_panic(expected = "is not ASCII")]
+    fn test_to_ascii_safe_replacement_not_ascii() {
+        let string: ImString<Threadsafe> = ImString::from("über");
+        string.to_ascii_safe('ü');
+    }
+
+    #[test]
+    fn test_wrap_shares() {
+        let string: ImString<Threadsafe> = ImString::from("some words\nto wrap");
+        for line in string.wrap(4) {
+            assert!(line.string.ptr_eq(&string.string));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "wrap width must be non-zero")]
+    fn test_wrap_zero_width() {
+        let string: ImString<Threadsafe> = ImString::from("hello");
+        string.wrap(0);
+    }
+
+    #[test]
+    fn test_replace_char_shares() {
+        let string: ImString<Threadsafe> = ImString::from("hello, world").slice(7..);
+        let replaced = string.replace_char('x', 'y');
+        assert_eq!(replaced, "world");
+        assert!(replaced.string.ptr_eq(&string.string));
+    }
+
+    #[test]
+    fn test_into_boxed_bytes_reuses_buffer() {
+        let string: ImString<Threadsafe> = ImString::from("hello");
+        let pointer = string.as_ptr();
+        let bytes = string.into_boxed_bytes();
+        assert_eq!(&*bytes, b"hello");
+        assert_eq!(bytes.as_ptr(), pointer);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_read_to_imstring() {
+        use std::io::Cursor;
+
+        let input = "first line\nsecond line\r\n\nlast";
+        let string: ImString<Threadsafe> = ImString::read_to_imstring(Cursor::new(input)).unwrap();
+        assert_eq!(string, input);
+        let lines: Vec<ImString<Threadsafe>> = string.lines().collect();
+        assert_eq!(lines, ["first line", "second line", "", "last"]);
+        for line in &lines {
+            assert!(line.string.ptr_eq(&string.string));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_read_to_imstring_invalid() {
+        use std::io::{Cursor, ErrorKind};
+
+        let result: std::io::Result<ImString<Threadsafe>> =
+            ImString::read_to_imstring(Cursor::new(b"invalid \xff utf-8"));
+        assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_trim_with_offsets_shares() {
+        let string: ImString<Threadsafe> = ImString::from("key = value ");
+        let (trimmed, start, end) = string.slice(5..).trim_with_offsets();
+        assert_eq!((trimmed.as_str(), start, end), ("value", 1, 1));
+        assert!(trimmed.string.ptr_eq(&string.string));
+    }
+
+    #[test]
+    fn test_search_line_shares() {
+        let string: ImString<Threadsafe> = ImString::from("alpha\nbeta\ngamma\n");
+        let line = string.search_line("gamma").unwrap();
+        assert_eq!(line.offset, 11..16);
+        assert!(line.string.ptr_eq(&string.string));
+    }
+
+    #[test]
+    fn test_take_while_char_shares() {
+        let string: ImString<Threadsafe> = ImString::from("let x = 1;").slice(4..);
+        let (name, rest) = string.take_while_char(char::is_alphanumeric);
+        assert_eq!((name.offset.clone(), rest.offset.clone()), (4..5, 5..10));
+        assert!(name.string.ptr_eq(&string.string));
+        assert!(rest.string.ptr_eq(&string.string));
+    }
+
+    #[test]
+    fn test_normalize_newlines_shares() {
+        let string: ImString<Threadsafe> = ImString::from("first\nsecond\n").slice(6..);
+        let normalized = string.normalize_newlines();
+        assert_eq!(normalized, "second\n");
+        assert!(normalized.string.ptr_eq(&string.string));
+    }
+
+    #[test]
+    fn test_strip_bom_shares() {
+        let string: ImString<Threadsafe> = ImString::from("\u{feff}data");
+        let stripped = string.strip_bom();
+        assert_eq!(stripped.offset, 3..7);
+        assert!(stripped.string.ptr_eq(&string.string));
+        let stripped = stripped.strip_bom();
+        assert_eq!(stripped.offset, 3..7);
+        assert!(stripped.string.ptr_eq(&string.string));
+    }
+
+    #[test]
+    fn test_leak_reuses_buffer() {
+        let string: ImString<Threadsafe> = ImString::from("hello, world");
+        let pointer = string.as_ptr();
+        let leaked = string.leak();
+        assert_eq!(leaked, "hello, world");
+        assert_eq!(leaked.as_ptr(), pointer);
+    }
+
+    #[test]
+    fn test_split_first_last_char_shares() {
+        let string: ImString<Threadsafe> = ImString::from("(abc)");
+        let (_, rest) = string.split_first_char().unwrap();
+        let (inner, _) = rest.split_last_char().unwrap();
+        assert_eq!(inner.offset, 1..4);
+        assert!(inner.string.ptr_eq(&string.string));
+    }
+
+    #[test]
+    fn test_replace_all_shares() {
+        let string: ImString<Threadsafe> = ImString::from("plain text").slice(6..);
+        let replaced = string.replace_all(&[("<", "&lt;"), (">", "&gt;")]);
+        assert_eq!(replaced, "text");
+        assert!(replaced.string.ptr_eq(&string.string));
+    }
+
+    fn test_reparent_strings<S: Data<String> + 'static>() {
+        for string in test_strings::<S>() {
+            let threadsafe: ImString<Threadsafe> = string.reparent();
+            let local: ImString<Local> = string.reparent();
+            let cloned: ImString<Cloned<String>> = string.reparent();
+            let boxed: ImString<Box<String>> = string.reparent();
+            let same: ImString<S> = string.reparent();
+            assert_eq!(threadsafe, string);
+            assert_eq!(local, string);
+            assert_eq!(cloned, string);
+            assert_eq!(boxed, string);
+            assert_eq!(same, string);
+            assert_eq!(threadsafe.reparent::<Local>(), string);
+            assert_eq!(local.reparent::<Box<String>>(), string);
+            assert_eq!(boxed.reparent::<Cloned<String>>(), string);
+            assert_eq!(cloned.reparent::<Threadsafe>(), string);
+        }
+    }
+
+    #[test]
+    fn test_reparent() {
+        test_reparent_strings::<Threadsafe>();
+        test_reparent_strings::<Local>();
+        test_reparent_strings::<Cloned<String>>();
+        test_reparent_strings::<Box<String>>();
+        #[cfg(feature = "triomphe")]
+        test_reparent_strings::<triomphe::Arc<String>>();
+    }
+
+    #[test]
+    fn test_reparent_copies() {
+        let string: ImString<Local> = ImString::from("hello, world");
+        let reparented: ImString<Threadsafe> = string.slice(7..).reparent();
+        assert_eq!(reparented.offset, 0..5);
+        assert_eq!(reparented.string.get(), "world");
+        let same: ImString<Local> = string.slice(7..).reparent();
+        assert_eq!(same.offset, 7..12);
+        assert!(same.string.ptr_eq(&string.string));
+    }
+
+    #[test]
+    fn test_from_cow_owned_reuses_buffer() {
+        let owned = String::from("hello, world");
+        let pointer = owned.as_ptr();
+        let string: ImString<Threadsafe> = ImString::from(Cow::<str>::Owned(owned));
+        assert_eq!(string, "hello, world");
+        assert_eq!(string.as_ptr(), pointer);
+
+        let borrowed = "hello, world";
+        let string: ImString<Threadsafe> = ImString::from(Cow::Borrowed(borrowed));
+        assert_eq!(string, borrowed);
+        assert_ne!(string.as_ptr(), borrowed.as_ptr());
+    }
+
+    #[test]
+    fn test_find_slice_shares() {
+        let string: ImString<Threadsafe> = ImString::from("key=value");
+        let needle = String::from("value");
+        let found = string.find_slice(&needle).unwrap();
+        assert_eq!(found.offset, 4..9);
+        assert!(found.string.ptr_eq(&string.string));
+    }
+
+    #[test]
+    fn test_from_raw_parts_shares() {
+        let string: ImString<Threadsafe> = ImString::from("hello world");
+        let world = ImString::from_raw_parts(string.raw_string(), 6..11).unwrap();
+        assert_eq!(world, "world");
+        assert!(world.string.ptr_eq(&string.string));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_line_col_at_out_of_bounds() {
+        let string: ImString<Threadsafe> = ImString::from("abc");
+        string.line_col_at(4);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_mutex_shared_mutation() {
+        use std::sync::{Arc, Mutex};
+
+        let shared = Arc::new(Mutex::new(ImString::<Threadsafe>::from("start")));
+        let snapshot = shared.lock().unwrap().clone();
+        let threads: Vec<_> = ["a", "b"]
+            .into_iter()
+            .map(|suffix| {
+                let shared = shared.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        shared.lock().unwrap().push_str(suffix);
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
         }
+
+        let result = shared.lock().unwrap().clone();
+        assert_eq!(result.len(), 205);
+        assert!(result.starts_with("start"));
+        assert_eq!(result.matches('a').count(), 101);
+        assert_eq!(result.matches('b').count(), 100);
+        assert_eq!(snapshot, "start");
+    }
+
+    #[test]
+    fn test_as_mut_str_sharing() {
+        let mut string: ImString<Threadsafe> = ImString::from("unique");
+        let pointer = string.as_ptr();
+        string.as_mut_str().make_ascii_uppercase();
+        assert_eq!(string, "UNIQUE");
+        assert_eq!(string.as_ptr(), pointer);
+
+        let clone = string.clone();
+        let mut slice = string.slice(2..);
+        slice.as_mut_str().make_ascii_lowercase();
+        assert_eq!(slice, "ique");
+        assert_eq!(clone, "UNIQUE");
+        assert!(!slice.string.ptr_eq(&clone.string));
     }
 }
@@ -1,5 +1,6 @@
 #![cfg(feature = "peg")]
 
+use imstr::data::Data;
 use imstr::ImString;
 
 #[test]
@@ -24,6 +25,7 @@ pub enum Atom {
 }
 
 #[test]
+#[allow(clippy::useless_conversion)]
 fn test_peg_medium() {
     peg::parser! {
         grammar list_parser() for ImString {
@@ -42,3 +44,62 @@ fn test_peg_medium() {
         ])
     );
 }
+
+#[test]
+fn test_peg_literal_ci() {
+    peg::parser! {
+        grammar query_parser() for ImString {
+            rule keyword(literal: &'static str) =
+                #{|input, pos| input.parse_string_literal_ci(pos, literal)}
+
+            rule name() -> ImString = $(['a'..='z']+)
+
+            pub rule select() -> ImString = keyword("select") " " n:name() { n }
+        }
+    }
+
+    assert_eq!(
+        query_parser::select(&"SELECT name".into()),
+        Ok("name".into())
+    );
+    assert_eq!(
+        query_parser::select(&"select name".into()),
+        Ok("name".into())
+    );
+    assert_eq!(
+        query_parser::select(&"SeLeCt name".into()),
+        Ok("name".into())
+    );
+    assert!(query_parser::select(&"SELEC name".into()).is_err());
+    assert!(query_parser::select(&"SEL".into()).is_err());
+}
+
+#[test]
+fn test_peg_slice_shares_backing() {
+    peg::parser! {
+        grammar pair_parser() for ImString {
+            rule name() -> ImString = $(['a'..='z']+)
+
+            rule span() -> (usize, usize) =
+                start:position!() ['a'..='z']+ end:position!() { (start, end) }
+
+            pub rule pair() -> (ImString, (usize, usize)) = k:name() "=" v:span() { (k, v) }
+        }
+    }
+
+    let input = ImString::from("key=value");
+    let (key, (start, end)) = pair_parser::pair(&input).unwrap();
+    let value = input.span(start, end);
+    assert_eq!(key, "key");
+    assert_eq!(value, "value");
+    assert!(key.raw_string().ptr_eq(&input.raw_string()));
+    assert!(value.raw_string().ptr_eq(&input.raw_string()));
+
+    // spans are relative to the parsed string, even if it is a slice itself
+    let input = ImString::from("[key=value]").slice(1..10);
+    let (key, (start, end)) = pair_parser::pair(&input).unwrap();
+    assert_eq!(key, "key");
+    assert_eq!(key.raw_offset(), 1..4);
+    assert_eq!(input.span(start, end), "value");
+    assert_eq!(input.span(start, end).raw_offset(), 5..10);
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "proptest")]
+
+use imstr::proptest::im_string_strategy;
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+proptest! {
+    #[test]
+    fn slice_matches_str(string in im_string_strategy(), start: Index, end: Index) {
+        let boundaries: Vec<usize> = (0..=string.len())
+            .filter(|index| string.is_char_boundary(*index))
+            .collect();
+        let start = boundaries[start.index(boundaries.len())];
+        let end = boundaries[end.index(boundaries.len())];
+        let range = start.min(end)..start.max(end);
+        let slice = string.slice(range.clone());
+        prop_assert_eq!(slice.as_str(), &string.as_str()[range]);
+    }
+
+    #[test]
+    fn ord_matches_str(left in im_string_strategy(), right in im_string_strategy()) {
+        prop_assert_eq!(left.cmp(&right), left.as_str().cmp(right.as_str()));
+        prop_assert_eq!(left == right, left.as_str() == right.as_str());
+    }
+}
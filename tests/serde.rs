@@ -0,0 +1,63 @@
+#![cfg(feature = "serde")]
+
+use imstr::string::{ImString, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[test]
+fn serialize_as_string() {
+    let string = imstr::ImString::from("hello, world");
+    assert_eq!(serde_json::to_string(&string).unwrap(), r#""hello, world""#);
+    assert_eq!(
+        serde_json::to_string(&string.slice(7..)).unwrap(),
+        r#""world""#
+    );
+    let string: ImString<Local> = ImString::from("local");
+    assert_eq!(serde_json::to_string(&string).unwrap(), r#""local""#);
+}
+
+#[test]
+fn deserialize_from_string() {
+    let string: imstr::ImString = serde_json::from_str(r#""hello\nworld""#).unwrap();
+    assert_eq!(string, "hello\nworld");
+    let string: ImString<Local> = serde_json::from_str(r#""plain""#).unwrap();
+    assert_eq!(string, "plain");
+    assert!(serde_json::from_str::<imstr::ImString>("12").is_err());
+}
+
+#[test]
+fn map_keys() {
+    let mut map: HashMap<imstr::ImString, i32> = HashMap::new();
+    map.insert("one".into(), 1);
+    map.insert("two".into(), 2);
+    let json = serde_json::to_string(&map).unwrap();
+    let decoded: HashMap<imstr::ImString, i32> = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded, map);
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value, serde_json::json!({"one": 1, "two": 2}));
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Entry {
+    name: imstr::ImString,
+    #[serde(flatten)]
+    fields: HashMap<imstr::ImString, imstr::ImString>,
+}
+
+#[test]
+fn flatten_map() {
+    let json = r#"{"name":"ferris","kind":"crab","color":"orange"}"#;
+    let entry: Entry = serde_json::from_str(json).unwrap();
+    assert_eq!(entry.name, "ferris");
+    assert_eq!(entry.fields.len(), 2);
+    assert_eq!(entry.fields[&imstr::ImString::from("kind")], "crab");
+    assert_eq!(entry.fields[&imstr::ImString::from("color")], "orange");
+
+    let value = serde_json::to_value(&entry).unwrap();
+    assert_eq!(
+        value,
+        serde_json::from_str::<serde_json::Value>(json).unwrap()
+    );
+    let decoded: Entry = serde_json::from_value(value).unwrap();
+    assert_eq!(decoded, entry);
+}
@@ -24,6 +24,7 @@ fn test_new() {
 }
 
 #[test]
+#[allow(clippy::into_iter_on_ref)]
 fn can_get_as_bytes() {
     for input in EXAMPLE_STRINGS.into_iter() {
         let string = ImString::from_std_string((*input).into());
@@ -32,6 +33,7 @@ fn can_get_as_bytes() {
 }
 
 #[test]
+#[allow(clippy::into_iter_on_ref)]
 fn can_deref() {
     for input in EXAMPLE_STRINGS.into_iter() {
         let string = ImString::from_std_string((*input).into());
@@ -79,6 +81,7 @@ fn test_push_str() {
 }
 
 #[test]
+#[allow(clippy::option_as_ref_deref)]
 fn test_from_str() {
     let owned: Option<ImString> = "string".parse().ok();
     assert_eq!(owned.as_ref().map(|s| &**s), Some("string"));
@@ -125,6 +128,7 @@ fn test_from_char() {
 }
 
 #[test]
+#[allow(clippy::char_lit_as_u8)]
 fn test_str_concat() {
     let a: ImString = "hello".into();
     let b: ImString = "world".into();